@@ -0,0 +1,13 @@
+use cosmwasm_schema::write_api;
+
+use cw721_custom::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use cw721_custom::state::Config;
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+        migrate: MigrateMsg<Config>,
+    }
+}
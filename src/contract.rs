@@ -5,9 +5,18 @@ use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
 use cw721::ContractInfoResponse;
 // use cw721::Cw721Query;
 
-use crate::helpers::clear_state;
+use crate::helpers::{
+    clear_state,
+    validate_config,
+    check_store_conf,
+    check_pause_windows,
+    check_config_locks,
+    check_unlocked,
+    move_holdings,
+    untrack_token,
+};
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg };
-use crate::state::{Config, CW721Contract, CONFIG};
+use crate::state::{Config, CW721Contract, LockArea, CONFIG, STORED_TOKENS, STORED_COUNT};
 
 use cw2::{get_contract_version, set_contract_version};
 
@@ -20,7 +29,7 @@ pub use cw721_base::{
 use crate::execute::{
     execute_transfer_batch,
     execute_freeze,
-    execute_update_conf,
+    execute_update_config,
     execute_burn,
     execute_burn_batch,
     execute_mint,
@@ -30,7 +39,20 @@ use crate::execute::{
     execute_store_conf,
     execute_pause,
     execute_unpause,
-    execute_unfreeze, execute_pledge,
+    execute_lock, execute_pledge,
+    execute_grant_role,
+    execute_revoke_role,
+    execute_schedule_pause,
+    execute_update_metadata,
+    execute_lock_metadata,
+    execute_update_traits,
+    execute_unstore,
+    execute_replace_stored,
+    execute_compute_rarity,
+    execute_set_svg_conf,
+    execute_upload_layers,
+    execute_clear_pause_schedule,
+    execute_clear_snapshots,
 };
 
 use crate::error::ContractError;
@@ -40,7 +62,31 @@ use crate::migration::migrate_with_conf;
 use crate::query::{
     query_config,
     query_nft_info_batch,
+    query_all_nft_info_batch,
+    query_nft_info,
+    query_all_nft_info,
     query_burnt_amount,
+    query_roles,
+    query_pause_schedule,
+    query_locks,
+    query_trait_changes,
+    query_tokens_by_trait,
+    query_trait_counts,
+    query_rarity,
+    query_svg_conf,
+    query_mint_status,
+    query_stored_tokens,
+    query_minted_tokens,
+    query_token_counts,
+    query_simulate_mint,
+    query_simulate_burn,
+    query_svg_layer,
+    query_rarity_rank,
+    query_stats,
+    query_balance,
+    query_holders,
+    query_owner_of_at_height,
+    query_balance_at_height,
     // query_burnt_list,
     // query_burned,
 };
@@ -55,24 +101,34 @@ pub fn instantiate(
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let cw721_contract = CW721Contract::default();
 
+    // defaults to 10 but never above the supply
+    let max_mint_batch = msg.max_mint_batch
+        .unwrap_or_else(|| std::cmp::min(Uint128::from(10u128), msg.token_supply));
+
     let config = Config {
         creator: msg.creator,
         name: msg.name.clone(),
         token_supply: msg.token_supply,
         token_total: Uint128::zero(),
         cost: msg.cost,
-        dates: Some(msg.dates).unwrap_or_default(),
-        max_mint_batch: Some(msg.max_mint_batch).unwrap_or_else(|| Some(Uint128::from(10u128))),
+        dates: msg.dates,
+        max_mint_batch: Some(max_mint_batch),
         burn: msg.burn,
         wallet: msg.wallet,
-        store_conf: Some(msg.store_conf).unwrap(),
+        store_conf: msg.store_conf,
         frozen: false,
         paused: false,
+        token_id_scheme: msg.token_id_scheme,
+        schema: msg.schema,
+        limits: msg.limits,
     };
 
+    validate_config(deps.api, &config)?;
+    check_store_conf(&config.store_conf)?;
+
     // set our version :)
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
@@ -93,17 +149,19 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Freeze{} => execute_freeze(deps, info),
-        ExecuteMsg::Unfreeze{} => execute_unfreeze(deps, info),
+        ExecuteMsg::Lock { areas } => execute_lock(deps, info, areas),
 
-        ExecuteMsg::Pause{} => execute_pause(deps, info),
+        ExecuteMsg::Pause { until } => execute_pause(deps, info, until),
         ExecuteMsg::Unpause{} => execute_unpause(deps, info),
+        ExecuteMsg::SchedulePause(window) => execute_schedule_pause(env, deps, info, window),
+        ExecuteMsg::ClearPauseSchedule {} => execute_clear_pause_schedule(deps, info),
 
         ExecuteMsg::Mint{} => execute_mint(env, deps, info),
         ExecuteMsg::MintBatch(mint_msg) => execute_mint_batch(env, deps, info, mint_msg),
@@ -117,14 +175,54 @@ pub fn execute(
         ExecuteMsg::StoreBatch(store_msg) => execute_store_batch(deps, info, store_msg),
         ExecuteMsg::StoreConf(msg) => execute_store_conf(deps, info, msg),
 
+        ExecuteMsg::Unstore { tokens } => execute_unstore(deps, info, tokens),
+        ExecuteMsg::ReplaceStored(msg) => execute_replace_stored(deps, info, msg),
+
+        ExecuteMsg::UpdateMetadata(msg) => execute_update_metadata(deps, info, vec![msg]),
+        ExecuteMsg::UpdateMetadataBatch { tokens } => execute_update_metadata(deps, info, tokens),
+        ExecuteMsg::UpdateTraits { token_id, set, remove } => execute_update_traits(deps, info, token_id, set, remove),
+        ExecuteMsg::LockMetadata { tokens } => execute_lock_metadata(deps, info, tokens),
+        ExecuteMsg::SetSvgConf(svg_conf) => execute_set_svg_conf(deps, info, svg_conf),
+        ExecuteMsg::UploadLayers { layers } => execute_upload_layers(deps, info, layers),
+        ExecuteMsg::ComputeRarity { limit, recompute_on_burn } => execute_compute_rarity(deps, info, limit, recompute_on_burn),
+        ExecuteMsg::ClearSnapshots { limit } => execute_clear_snapshots(env, deps, info, limit),
+
         ExecuteMsg::TransferBatch(transfer) => execute_transfer_batch(env, deps, info, transfer),
 
-        ExecuteMsg::UpdateConf(msg) => execute_update_conf(deps, info, msg),
+        ExecuteMsg::UpdateConfig(msg) => execute_update_config(deps, info, msg),
+
+        ExecuteMsg::GrantRole { address, role } => execute_grant_role(deps, info, address, role),
+        ExecuteMsg::RevokeRole { address, role } => execute_revoke_role(deps, info, address, role),
 
         // CW721 methods
-        _ => CW721Contract::default()
-            .execute(deps, env, info, msg.into())
-            .map_err(|err| err.into()),
+        _ => {
+            let cw721_contract = CW721Contract::default();
+
+            let transferred = match &msg {
+                ExecuteMsg::TransferNft { token_id, .. } | ExecuteMsg::SendNft { token_id, .. } => {
+                    // transfers respect the scheduled pause windows
+                    check_pause_windows(deps.storage, &env.block)?;
+
+                    Some((token_id.clone(), cw721_contract.tokens.load(deps.storage, token_id)?.owner))
+                },
+                _ => None,
+            };
+
+            let height = env.block.height;
+
+            let res = cw721_contract
+                .execute(deps.branch(), env, info, msg.into())
+                .map_err(ContractError::from)?;
+
+            // keep the owner snapshots and holder balances in sync
+            if let Some((token_id, previous_owner)) = transferred {
+                let owner = cw721_contract.tokens.load(deps.storage, &token_id)?.owner;
+                untrack_token(deps.storage, &STORED_TOKENS, &STORED_COUNT, &token_id)?;
+                move_holdings(deps.storage, height, &[token_id], Some(&previous_owner), Some(&owner))?;
+            }
+
+            Ok(res)
+        },
     }
 }
 
@@ -136,10 +234,36 @@ pub fn query(
 ) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::NftInfoBatch { tokens } => to_binary(&query_nft_info_batch(deps, tokens)?),
+        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo { token_id, include_expired } => to_binary(&query_all_nft_info(deps, env, token_id, include_expired)?),
+        QueryMsg::NftInfoBatch { tokens, start_after, limit } => to_binary(&query_nft_info_batch(deps, tokens, start_after, limit)?),
+        QueryMsg::AllNftInfoBatch { tokens, include_expired, start_after, limit } => {
+            to_binary(&query_all_nft_info_batch(deps, env, tokens, include_expired, start_after, limit)?)
+        },
         QueryMsg::BurntAmount { address } => to_binary(&query_burnt_amount(deps, address)?),
-        // QueryMsg::BurntList { address } => to_binary(&query_burnt_list(deps, address)?),
-        // QueryMsg::Burned { tokens } => to_binary(&query_burned(deps, tokens)?),
+        QueryMsg::Roles { address } => to_binary(&query_roles(deps, address)?),
+        QueryMsg::PauseSchedule {} => to_binary(&query_pause_schedule(deps, env)?),
+        QueryMsg::Locks {} => to_binary(&query_locks(deps)?),
+        QueryMsg::TraitChanges { token_id } => to_binary(&query_trait_changes(deps, token_id)?),
+        QueryMsg::TokensByTrait { trait_type, value, start_after, limit } => {
+            to_binary(&query_tokens_by_trait(deps, trait_type, value, start_after, limit)?)
+        },
+        QueryMsg::TraitCounts { trait_type } => to_binary(&query_trait_counts(deps, trait_type)?),
+        QueryMsg::StoredTokens { start_after, limit } => to_binary(&query_stored_tokens(deps, start_after, limit)?),
+        QueryMsg::MintedTokens { start_after, limit } => to_binary(&query_minted_tokens(deps, start_after, limit)?),
+        QueryMsg::TokenCounts {} => to_binary(&query_token_counts(deps)?),
+        QueryMsg::MintStatus {} => to_binary(&query_mint_status(deps, env)?),
+        QueryMsg::SimulateMint { sender, amount, funds } => to_binary(&query_simulate_mint(deps, env, sender, amount, funds)?),
+        QueryMsg::SimulateBurn { sender, tokens } => to_binary(&query_simulate_burn(deps, env, sender, tokens)?),
+        QueryMsg::SvgConf {} => to_binary(&query_svg_conf(deps)?),
+        QueryMsg::SvgLayer { trait_type, value } => to_binary(&query_svg_layer(deps, trait_type, value)?),
+        QueryMsg::Rarity { token_id } => to_binary(&query_rarity(deps, token_id)?),
+        QueryMsg::RarityRank { start_after, limit } => to_binary(&query_rarity_rank(deps, start_after, limit)?),
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::Balance { owner } => to_binary(&query_balance(deps, owner)?),
+        QueryMsg::Holders { start_after, limit } => to_binary(&query_holders(deps, start_after, limit)?),
+        QueryMsg::OwnerOfAtHeight { token_id, height } => to_binary(&query_owner_of_at_height(deps, token_id, height)?),
+        QueryMsg::BalanceAtHeight { owner, height } => to_binary(&query_balance_at_height(deps, owner, height)?),
         // CW721 methods
         _ => CW721Contract::default().query(deps, env, msg.into()),
     }
@@ -148,11 +272,23 @@ pub fn query(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: MigrateMsg<Config>,
 ) -> Result<Response, ContractError> {
     match msg {
-        MigrateMsg::WithConfig { version, config } => {
+        MigrateMsg::WithConfig { version, mut config } => {
+            let previous = CONFIG.load(deps.storage)?;
+
+            // the stored total follows the tokens, not the migration
+            config.token_total = previous.token_total;
+
+            validate_config(deps.api, &config)?;
+            check_config_locks(deps.storage, &config)?;
+
+            if config.store_conf != previous.store_conf {
+                check_store_conf(&config.store_conf)?;
+            }
+
             let current = get_contract_version(deps.storage)?;
 
             if current.version != version {
@@ -170,15 +306,29 @@ pub fn migrate(
 
             Err(ContractError::MigrationSameVersion { version })
         },
-        MigrateMsg::WithConfigClearState { version, config } => {
+        MigrateMsg::WithConfigClearState { version, mut config } => {
+            // nothing is stored once the state is cleared
+            config.token_total = Uint128::zero();
+
+            validate_config(deps.api, &config)?;
+
+            if config.store_conf != CONFIG.load(deps.storage)?.store_conf {
+                check_store_conf(&config.store_conf)?;
+            }
+
+            // clearing the state drops the stored tokens
+            check_unlocked(deps.storage, LockArea::Metadata)?;
+            check_unlocked(deps.storage, LockArea::Supply)?;
+
             let current = get_contract_version(deps.storage)?;
 
             if current.version != version {
                 let cw721_contract = CW721Contract::default();
 
-                clear_state(deps.storage)?;
+                clear_state(deps.storage, env.block.height)?;
 
                 cw721_contract.tokens.clear(deps.storage);
+                cw721_contract.token_count.save(deps.storage, &0)?;
 
                 let res = migrate_with_conf(deps.storage, version.clone(), config);
 
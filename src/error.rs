@@ -1,7 +1,9 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use cw721_base::ContractError as CW721ContractError;
 use thiserror::Error;
 
+use crate::state::{LockArea, Role};
+
 /// This overrides the ContractError enum defined in cw721-base
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -14,6 +16,9 @@ pub enum ContractError {
     #[error("Unauthorized to execute request: {msg}")]
     UnauthorizedWithMsg { msg: String },
 
+    #[error("Unauthorized to execute request, missing role: {role:?}")]
+    MissingRole { role: Role },
+
     #[error("Approval not found for: {spender}")]
     ApprovalNotFound { spender: String },
 
@@ -41,6 +46,12 @@ pub enum ContractError {
     #[error("Token ({token_id}) not found")]
     TokenNotFound { token_id: String },
 
+    #[error("Config update has no fields")]
+    EmptyConfigUpdate {},
+
+    #[error("Token id ({token_id}) does not match the token id scheme, expected ({expected})")]
+    TokenIdMismatch { token_id: String, expected: String },
+
     #[error("Token is not pledged ({token_id})")]
     TokenNotPledged { token_id: String },
 
@@ -50,21 +61,45 @@ pub enum ContractError {
     #[error("Contract is frozen")]
     ContractFrozen {},
 
+    #[error("Locked for good: {area:?}")]
+    Locked { area: LockArea },
+
+    #[error("Token id scheme can not change once tokens are stored")]
+    TokenIdSchemeInUse {},
+
+    #[error("Only the last stored tokens can be removed, expected ({token_id})")]
+    UnstoreNotLast { token_id: String },
+
+    #[error("Token ({token_id}) has already been minted")]
+    TokenMinted { token_id: String },
+
+    #[error("Token ({token_id}) metadata is locked")]
+    TokenMetadataLocked { token_id: String },
+
     #[error("Contract is paused")]
     ContractPaused {},
 
+    #[error("Contract is paused by a scheduled window")]
+    ContractPausedScheduled {},
+
+    #[error("Invalid pause window, end is not after start")]
+    InvalidPauseWindow {},
+
     #[error("Token supply exhausted")]
     MaxTokenSupply {},
 
     #[error("Token total reached")]
     MaxTokens {},
 
-    #[error("Request too large {size}")]
-    RequestTooLarge { size: usize },
+    #[error("Request too large {size}, max {max}")]
+    RequestTooLarge { size: usize, max: u32 },
 
     #[error("Request too small {size}")]
     RequestTooSmall { size: usize },
 
+    #[error("Snapshots are still being cleared")]
+    SnapshotsNotCleared {},
+
     //NotEnoughFunds
     #[error("Not enough funds")]
     NotEnoughFunds {},
@@ -105,6 +140,69 @@ pub enum ContractError {
     #[error("Failed to update burn token list")]
     FailedBurnedUpdateList {},
 
+    #[error("Token supply ({supply}) can not be lower than the stored total ({total})")]
+    SupplyBelowTotal { supply: Uint128, total: Uint128 },
+
+    #[error("Invalid dates, mint end is not after mint start")]
+    InvalidDates {},
+
+    #[error("Token supply can not be zero")]
+    SupplyZero {},
+
+    #[error("Mint cost can not be zero")]
+    CostZero {},
+
+    #[error("Mint cost denom can not be empty")]
+    CostDenomEmpty {},
+
+    #[error("Invalid wallet address: {address}")]
+    InvalidWallet { address: String },
+
+    #[error("Invalid creator address: {address}")]
+    InvalidCreator { address: String },
+
+    #[error("Store conf declares {attributes} trait types but {values} value lists")]
+    InvalidAttributeDictionary { attributes: usize, values: usize },
+
+    #[error("Row {row} has {got} attributes, expected {expected}")]
+    InvalidAttributeArity { row: usize, expected: usize, got: usize },
+
+    #[error("Row {row} index {index} is out of bounds for {trait_type}")]
+    AttributeIndexOutOfBounds { row: usize, trait_type: String, index: u32 },
+
+    #[error("Token {token_id} has trait type {trait_type} which is not in the schema")]
+    UnknownTraitType { token_id: String, trait_type: String },
+
+    #[error("Token {token_id} has value {value} not allowed for {trait_type}")]
+    InvalidTraitValue { token_id: String, trait_type: String, value: String },
+
+    #[error("Token {token_id} is missing the required trait {trait_type}")]
+    MissingTrait { token_id: String, trait_type: String },
+
+    #[error("Token {token_id} is missing the required field {field}")]
+    MissingMetadataField { token_id: String, field: String },
+
+    #[error("Token {token_id} {field} uses a url scheme not in the schema: {url}")]
+    InvalidUrlScheme { token_id: String, field: String, url: String },
+
+    #[error("Token {token_id} {field} is longer than {max} characters")]
+    FieldTooLong { token_id: String, field: String, max: u32 },
+
+    #[error("Limits can not be zero and defaults can not be above their max")]
+    InvalidLimits {},
+
+    #[error("Burning is disabled by the configuration")]
+    BurnDisabled {},
+
+    #[error("Metadata must be locked to compute rarity")]
+    RarityMetadataUnlocked {},
+
+    #[error("Max mint batch can not be zero")]
+    MaxMintBatchZero {},
+
+    #[error("Max mint batch ({max_mint_batch}) is larger than the token supply ({supply})")]
+    MaxMintBatchTooLarge { max_mint_batch: Uint128, supply: Uint128 },
+
     #[error["Failed to update total tokens"]]
     CantUpdateTotal {},
 
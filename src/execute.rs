@@ -6,6 +6,7 @@ use cosmwasm_std::{
     BankMsg,
     CosmosMsg,
     Uint128, Storage, StdError, StdResult,
+    Order,
 };
 
 use cw721_base::{ MintMsg };
@@ -17,19 +18,62 @@ use crate::state::{
     CONFIG,
     Metadata,
     Trait,
-    Config, PLEDGED_TOKENS_BY_ADDR, PLEDGED_TOKENS,
+    PLEDGED_TOKENS_BY_ADDR, PLEDGED_TOKENS,
+    Role, ROLES,
+    PAUSE_WINDOWS, PAUSE_EXPIRES,
+    LockArea, Locks, LOCKS,
+    TOKEN_METADATA_LOCKS,
+    CompactToken, COMPACT_TOKENS,
+    STORE_CONFS, STORE_CONF_VERSION,
+    RARITY_STATE, RARITY_RANK, RARITY_RANKS, TRAITED_COUNT,
+    SvgConf, SvgLayer, SVG_CONF, SVG_LAYERS,
+    STORED_TOKENS, STORED_COUNT,
+    STATS,
 };
 
+use cw_storage_plus::Bound;
+
+use crate::types_mint::PauseWindow;
+
+use cw_utils::Scheduled;
+
 use crate::helpers::{
     transfer_nft,
     can_mint,
     can_pay,
     can_store,
     can_update,
+    can_execute,
+    has_role,
     try_mint,
     try_store,
     burn_and_update,
     update_total,
+    validate_config,
+    check_pause_windows,
+    check_config_locks,
+    check_unlocked,
+    load_locks,
+    try_update_metadata,
+    try_update_traits,
+    try_unstore,
+    check_stored,
+    build_store_conf_token,
+    check_store_conf,
+    expand_attributes,
+    index_traits,
+    load_token,
+    extension_traits,
+    rarity_score,
+    save_rarity,
+    validate_metadata,
+    check_burnable,
+    track_token,
+    check_batch_size,
+    record_revenue,
+    record_mints,
+    clear_snapshots,
+    move_holdings,
 };
 
 use crate::error::ContractError;
@@ -38,9 +82,10 @@ use crate::msg::{
     BatchStoreMsg,
     MintBatchMsg,
     StoreConfMsg,
-    InstantiateMsg, TransferOperation
+    UpdateConfigMsg, UpdateMetadataMsg, TransferOperation
 };
 
+// Freeze is the final lock, every area is locked for good
 pub fn execute_freeze(
     deps: DepsMut,
     info: MessageInfo,
@@ -51,6 +96,7 @@ pub fn execute_freeze(
     config.frozen = true;
 
     CONFIG.save(deps.storage, &config)?;
+    LOCKS.save(deps.storage, &Locks::all())?;
 
     Ok(
         Response::new()
@@ -58,49 +104,63 @@ pub fn execute_freeze(
     )
 }
 
-pub fn execute_unfreeze(
+pub fn execute_lock(
     deps: DepsMut,
     info: MessageInfo,
+    areas: Vec<LockArea>,
 ) -> Result<Response, ContractError> {
     can_update(&deps, &info)?;
 
-    let mut config = CONFIG.load(deps.storage)?;
-    config.frozen = false;
+    let mut locks = load_locks(deps.storage)?;
 
-    CONFIG.save(deps.storage, &config)?;
+    areas.iter().for_each(|area| locks.lock(area));
+
+    LOCKS.save(deps.storage, &locks)?;
 
     Ok(
         Response::new()
-            .add_attribute("action", "unfreeze")
+            .add_attribute("action", "lock")
+            .add_attribute("areas", format!("{:?}", areas))
     )
 }
 
 pub fn execute_pause(
     deps: DepsMut,
     info: MessageInfo,
+    until: Option<Scheduled>,
 ) -> Result<Response, ContractError> {
-    can_update(&deps, &info)?;
+    can_execute(&deps, &info, Role::Pauser)?;
 
     let mut config = CONFIG.load(deps.storage)?;
     config.paused = true;
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(
-        Response::new()
-            .add_attribute("action", "pause")
-    )
+    let mut response = Response::new()
+        .add_attribute("action", "pause");
+
+    // automatically unpause once triggered
+    match until {
+        Some(until) => {
+            PAUSE_EXPIRES.save(deps.storage, &until)?;
+            response = response.add_attribute("until", until.to_string());
+        },
+        None => PAUSE_EXPIRES.remove(deps.storage),
+    }
+
+    Ok(response)
 }
 
 pub fn execute_unpause(
     deps: DepsMut,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
-    can_update(&deps, &info)?;
+    can_execute(&deps, &info, Role::Pauser)?;
 
     let mut config = CONFIG.load(deps.storage)?;
     config.paused = false;
 
     CONFIG.save(deps.storage, &config)?;
+    PAUSE_EXPIRES.remove(deps.storage);
 
     Ok(
         Response::new()
@@ -108,33 +168,203 @@ pub fn execute_unpause(
     )
 }
 
-pub fn execute_update_conf(
+pub fn execute_schedule_pause(
+    env: Env,
     deps: DepsMut,
     info: MessageInfo,
-    msg: InstantiateMsg,
+    window: PauseWindow,
 ) -> Result<Response, ContractError> {
-    can_update(&deps, &info)?;
+    can_execute(&deps, &info, Role::Pauser)?;
 
-    let config = CONFIG.load(deps.storage)?;
+    let valid = match (&window.start, &window.end) {
+        (Scheduled::AtHeight(start), Scheduled::AtHeight(end)) => start < end,
+        (Scheduled::AtTime(start), Scheduled::AtTime(end)) => start < end,
+        // mixed windows can only be checked against the current block
+        _ => true,
+    };
+
+    if !valid || window.is_over(&env.block) {
+        return Err(ContractError::InvalidPauseWindow {})
+    }
+
+    let mut windows = PAUSE_WINDOWS.may_load(deps.storage)?.unwrap_or_default();
+
+    // drop the windows that are already over
+    windows.retain(|item| !item.is_over(&env.block));
+    windows.push(window);
+
+    PAUSE_WINDOWS.save(deps.storage, &windows)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "schedule_pause")
+            .add_attribute("windows", windows.len().to_string())
+    )
+}
+
+pub fn execute_clear_pause_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    can_execute(&deps, &info, Role::Pauser)?;
+
+    PAUSE_WINDOWS.remove(deps.storage);
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "clear_pause_schedule")
+    )
+}
+
+// Remove the snapshots left by a clear state migration, in batches
+pub fn execute_clear_snapshots(
+    env: Env,
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    can_execute(&deps, &info, Role::Admin)?;
+
+    let max = CONFIG.load(deps.storage)?.limits.max_token_batch;
+    let limit = limit.unwrap_or(max).min(max) as usize;
+
+    let (removed, done) = clear_snapshots(deps.storage, env.block.height, limit)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "clear_snapshots")
+            .add_attribute("removed", removed.to_string())
+            .add_attribute("done", done.to_string())
+    )
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    // an empty update would skip every role check below
+    if msg == UpdateConfigMsg::default() {
+        return Err(ContractError::EmptyConfigUpdate {})
+    }
+
+    // payout fields belong to the treasurer
+    if msg.cost.is_some() || msg.wallet.is_some() {
+        can_execute(&deps, &info, Role::Treasurer)?;
+    }
+
+    // everything else requires an admin
+    if msg.name.is_some()
+        || msg.token_supply.is_some()
+        || msg.dates.is_some()
+        || msg.max_mint_batch.is_some()
+        || msg.burn.is_some()
+        || msg.store_conf.is_some()
+        || msg.token_id_scheme.is_some()
+        || msg.schema.is_some()
+        || msg.limits.is_some() {
+        can_update(&deps, &info)?;
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
 
     if config.frozen {
         return Err(ContractError::ContractFrozen {  })
     }
 
-    let config = Config {
-        creator: msg.creator,
-        name: msg.name,
-        token_supply: msg.token_supply,
-        token_total: Uint128::zero(),
-        cost: msg.cost,
-        dates: Some(msg.dates).unwrap_or_default(),
-        max_mint_batch: Some(msg.max_mint_batch).unwrap_or_else(|| Some(Uint128::from(10u128))),
-        burn: msg.burn,
-        wallet: msg.wallet,
-        store_conf: msg.store_conf,
-        frozen: false,
-        paused: false,
-    };
+    let mut changed: Vec<&str> = vec![];
+
+    if let Some(name) = msg.name {
+        if name != config.name {
+            let contract = CW721Contract::default();
+            let mut contract_info = contract.contract_info.load(deps.storage)?;
+            contract_info.name = name.clone();
+            contract.contract_info.save(deps.storage, &contract_info)?;
+
+            config.name = name;
+            changed.push("name");
+        }
+    }
+
+    if let Some(token_supply) = msg.token_supply {
+        if token_supply != config.token_supply {
+            config.token_supply = token_supply;
+            changed.push("token_supply");
+        }
+    }
+
+    if let Some(cost) = msg.cost {
+        if cost != config.cost {
+            config.cost = cost;
+            changed.push("cost");
+        }
+    }
+
+    if let Some(dates) = msg.dates {
+        if dates != config.dates {
+            config.dates = dates;
+            changed.push("dates");
+        }
+    }
+
+    if let Some(max_mint_batch) = msg.max_mint_batch {
+        if Some(max_mint_batch) != config.max_mint_batch {
+            config.max_mint_batch = Some(max_mint_batch);
+            changed.push("max_mint_batch");
+        }
+    }
+
+    if let Some(burn) = msg.burn {
+        if burn != config.burn {
+            config.burn = burn;
+            changed.push("burn");
+        }
+    }
+
+    if let Some(wallet) = msg.wallet {
+        if wallet != config.wallet {
+            config.wallet = wallet;
+            changed.push("wallet");
+        }
+    }
+
+    if let Some(store_conf) = msg.store_conf {
+        if store_conf != config.store_conf {
+            check_store_conf(&store_conf)?;
+
+            config.store_conf = store_conf;
+            changed.push("store_conf");
+        }
+    }
+
+    if let Some(token_id_scheme) = msg.token_id_scheme {
+        if token_id_scheme != config.token_id_scheme {
+            // stored tokens would not be found by mint anymore
+            if !config.token_total.is_zero() {
+                return Err(ContractError::TokenIdSchemeInUse {})
+            }
+
+            config.token_id_scheme = token_id_scheme;
+            changed.push("token_id_scheme");
+        }
+    }
+
+    if let Some(schema) = msg.schema {
+        if Some(&schema) != config.schema.as_ref() {
+            config.schema = Some(schema);
+            changed.push("schema");
+        }
+    }
+
+    if let Some(limits) = msg.limits {
+        if limits != config.limits {
+            config.limits = limits;
+            changed.push("limits");
+        }
+    }
+
+    validate_config(deps.api, &config)?;
+    check_config_locks(deps.storage, &config)?;
 
     CONFIG.save(deps.storage, &config)?;
 
@@ -143,6 +373,59 @@ pub fn execute_update_conf(
             .add_attribute("action", "config")
             .add_attribute("sub", "update")
             .add_attribute("result", "success")
+            .add_attribute("changed", changed.join(","))
+    )
+}
+
+pub fn execute_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    can_update(&deps, &info)?;
+
+    let address = deps.api.addr_validate(&address)?;
+
+    let mut roles = ROLES.may_load(deps.storage, &address)?.unwrap_or_default();
+
+    if !roles.contains(&role) {
+        roles.push(role.clone());
+        ROLES.save(deps.storage, &address, &roles)?;
+    }
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("address", address)
+            .add_attribute("role", format!("{:?}", role))
+    )
+}
+
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    can_update(&deps, &info)?;
+
+    let address = deps.api.addr_validate(&address)?;
+
+    let mut roles = ROLES.may_load(deps.storage, &address)?.unwrap_or_default();
+    roles.retain(|item| *item != role);
+
+    if roles.is_empty() {
+        ROLES.remove(deps.storage, &address);
+    } else {
+        ROLES.save(deps.storage, &address, &roles)?;
+    }
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("address", address)
+            .add_attribute("role", format!("{:?}", role))
     )
 }
 
@@ -152,6 +435,10 @@ pub fn execute_transfer_batch(
     info: MessageInfo,
     transfer: TransferOperation,
 ) -> Result<Response, ContractError> {
+    check_pause_windows(deps.storage, &env.block)?;
+
+    check_batch_size(transfer.tokens.len(), CONFIG.load(deps.storage)?.limits.max_token_batch)?;
+
     let cw721_contract = CW721Contract::default();
 
     let recipient_address = &deps.api.addr_validate(&transfer.recipient)?;
@@ -194,9 +481,10 @@ pub fn execute_pledge(
     info: MessageInfo,
     tokens: Vec<String>,
 ) -> Result<Response, ContractError> {
-    let cw721_contract = CW721Contract::default();
+    check_batch_size(tokens.len(), CONFIG.load(deps.storage)?.limits.max_token_batch)?;
 
-    let config = CONFIG.load(deps.storage)?;
+    let cw721_contract = CW721Contract::default();
+    let is_burner = has_role(deps.storage, &info.sender, &Role::Burner)?;
 
     let mut response: Response = Response::default().add_attribute("action", "pledge");
 
@@ -209,7 +497,7 @@ pub fn execute_pledge(
 
             let token = cw721_contract.tokens.load(deps.storage, &token_id)?;
 
-            if info.sender == token.owner || info.sender == config.creator {
+            if info.sender == token.owner || is_burner {
                 if PLEDGED_TOKENS.has(deps.storage, token_id.clone()) {
                     return Err(StdError::GenericErr { msg: ContractError::TokenPledged { token_id: token_id.clone() }.to_string() });
                 }
@@ -224,6 +512,10 @@ pub fn execute_pledge(
         .collect::<StdResult<Vec<_>>>()?;
 
     if !pledged_list.is_empty() {
+        let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+        stats.pledged += pledged_list.len() as u64;
+        STATS.save(deps.storage, &stats)?;
+
         // check if address has more pledged tokens and add them
         if PLEDGED_TOKENS_BY_ADDR.has(deps.storage, &info.sender) {
             let mut old = PLEDGED_TOKENS_BY_ADDR.load(deps.storage, &info.sender)?;
@@ -244,6 +536,8 @@ pub fn execute_burn(
     info: MessageInfo,
     token_id: String,
 ) -> Result<Response, ContractError> {
+    check_pause_windows(deps.storage, &env.block)?;
+
     if !PLEDGED_TOKENS.has(deps.storage, token_id.clone()) {
         return Err(ContractError::TokenNotPledged { token_id })
     }
@@ -270,7 +564,7 @@ pub fn execute_burn(
             .add_attribute("token_id", token_id))
     }
 
-    if config.burn.can_burn_owned && config.creator == info.sender {
+    if config.burn.can_burn_owned && has_role(deps.storage, &info.sender, &Role::Burner)? {
         burn_and_update(
             &cw721_contract,
             deps.storage,
@@ -305,15 +599,20 @@ pub fn execute_burn_batch(
     info: MessageInfo,
     tokens: Vec<String>
 ) -> Result<Response, ContractError> {
+    check_pause_windows(deps.storage, &env.block)?;
+
     let cw721_contract = CW721Contract::default();
     let config = CONFIG.load(deps.storage)?;
+    let is_burner = has_role(deps.storage, &info.sender, &Role::Burner)?;
 
-    if tokens.len() >= 30 {
-        return Err(ContractError::RequestTooLarge{ size: tokens.len() })
-    }
+    check_batch_size(tokens.len(), config.limits.max_token_batch)?;
 
     if tokens.is_empty() {
-        return Err(ContractError::RequestTooSmall{ size: tokens.len() })
+        return Err(ContractError::RequestTooSmall { size: tokens.len() })
+    }
+
+    if !config.burn.owner_can_burn && !config.burn.can_burn_owned {
+        return Err(ContractError::BurnDisabled {})
     }
 
     let mut response: Response = Response::default();
@@ -335,59 +634,33 @@ pub fn execute_burn_batch(
         )
     };
 
-    let mut process_tokens = |tokens: Vec<String>| -> Vec<String> {
+    let mut process_tokens = |tokens: Vec<String>| -> Result<Vec<String>, ContractError> {
         tokens
             .into_iter()
-            .map(|token_id| {
-                if !cw721_contract.tokens.has(deps.storage, &token_id) {
-                    return Err(StdError::GenericErr { msg: ContractError::DontExists { }.to_string() })
-                }
+            .map(|token_id| -> Result<String, ContractError> {
+                let token = check_burnable(&cw721_contract, deps.storage, &info.sender, is_burner, &token_id)?;
 
-                if !PLEDGED_TOKENS.has(deps.storage, token_id.clone()) {
-                    return Err(StdError::GenericErr { msg: ContractError::TokenNotPledged { token_id: token_id.clone() }.to_string() })
-                }
-
-                let token = cw721_contract.tokens.load(deps.storage, token_id.as_str())?;
-
-                if token.owner == info.sender || config.creator == info.sender {
-                    let res = call_burn_and_update(deps.storage, &token, &token_id, false);
-                    if res.is_err() {
-                        return Err(StdError::GenericErr { msg: res.err().unwrap().to_string() })
-                    }
-                } else {
-                    return Err(StdError::GenericErr { msg: ContractError::UnauthorizedWithMsg {
-                        msg: "sender is not owner".to_string()
-                    }.to_string() })
-                }
+                call_burn_and_update(deps.storage, &token, &token_id, false)?;
 
                 Ok(token_id)
             })
-            .collect::<StdResult<Vec<_>>>()
-            .unwrap()
+            .collect()
     };
 
-    if config.burn.owner_can_burn || config.burn.can_burn_owned {
-        let token_list = process_tokens(tokens);
+    let token_list = process_tokens(tokens)?;
 
-        if token_list.len() > 0 {
-            response = response.add_attribute("action", "burn_batch");
+    if token_list.len() > 0 {
+        response = response.add_attribute("action", "burn_batch");
 
-            if info.sender == config.creator {
-                response = response.add_attribute("sub", "creator_burn")
-            } else {
-                response = response.add_attribute("sub", "owner_burn")
-            }
+        if is_burner {
+            response = response.add_attribute("sub", "creator_burn")
+        } else {
+            response = response.add_attribute("sub", "owner_burn")
         }
-
-        response = response.add_attribute("results", format!("{:?}", token_list));
-    } else {
-        response = response
-            .add_attribute("action", "burn_nothing")
-            .add_attribute("why", "configuration")
-            .add_attribute("owners_can_burn", config.burn.owner_can_burn.to_string())
-            .add_attribute("creator_can_burn", "true")
     }
 
+    response = response.add_attribute("results", format!("{:?}", token_list));
+
     Ok(response)
 }
 
@@ -404,8 +677,9 @@ pub fn execute_mint(
 
     // check if we can mint
     let current_token_id = can_mint(
+        deps.storage,
+        &env.block,
         &current_count,
-        &env.block.time,
         &config,
         &mint_amount,
         &minter,
@@ -415,20 +689,26 @@ pub fn execute_mint(
     // validate funds according to set price
     let coin_found = can_pay(&config, &info, &mint_amount)?;
 
+    let token_id = config.token_id_scheme.token_id(current_token_id);
+
     try_mint(
         deps.storage,
         &info.sender,
         &minter,
         &cw721_contract,
-        &current_token_id.to_string()
+        &token_id
     )?;
 
+    move_holdings(deps.storage, env.block.height, &[token_id.clone()], Some(&minter), Some(&info.sender))?;
+    record_revenue(deps.storage, &coin_found)?;
+    record_mints(deps.storage, config.dates.phase(&env.block.time), &config.dates, 1)?;
+
     // send funds to the configured funds wallet
     // send the info below
     Ok(Response::new()
         .add_attribute("action", "mint")
         .add_attribute("owner", info.sender)
-        .add_attribute("token_id", current_count.to_string())
+        .add_attribute("token_id", token_id)
         .add_message(
             CosmosMsg::Bank(BankMsg::Send {
                 to_address: config.wallet.wallet.to_string(),
@@ -455,8 +735,9 @@ pub fn execute_mint_batch(
 
     // check if we can mint
     let mut current_token_id = can_mint(
+        deps.storage,
+        &env.block,
         &minted_total,
-        &env.block.time,
         &config,
         &msg.amount,
         &minter,
@@ -477,18 +758,20 @@ pub fn execute_mint_batch(
         .add_attribute("requested", msg.amount.to_string());
 
     while Uint128::from(total_minted) < mint_amount {
+        let token_id = config.token_id_scheme.token_id(current_token_id);
+
         //atempt to mint
         try_mint(
             deps.storage,
             &info.sender,
             &minter,
             &cw721_contract,
-            &current_token_id.to_string()
+            &token_id
         )?;
 
         total_minted += 1;
         current_token_id += Uint128::one();
-        ids.push(current_token_id.to_string())
+        ids.push(token_id)
     }
 
     coin_found.amount = config.cost.amount * Uint128::from(total_minted);
@@ -497,6 +780,10 @@ pub fn execute_mint_batch(
         .add_attribute("cost", coin_found.amount.to_string())
         .add_attribute("list", format!("{:?}", ids));
 
+    move_holdings(deps.storage, env.block.height, &ids, Some(&minter), Some(&info.sender))?;
+    record_revenue(deps.storage, &coin_found)?;
+    record_mints(deps.storage, config.dates.phase(&env.block.time), &config.dates, total_minted as u64)?;
+
     // send funds to the configured funds wallet
     response_msg = response_msg.add_message(
         CosmosMsg::Bank(BankMsg::Send {
@@ -518,15 +805,18 @@ pub fn execute_store(
 
     let cw721_contract = CW721Contract::default();
     let minter = cw721_contract.minter.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
 
-    try_store(deps.storage, &nft_data, &minter, &cw721_contract)?;
+    let token_id = config.token_id_scheme.token_id(config.token_total);
 
-    let total = CONFIG.load(deps.storage)?.token_total + Uint128::one();
-    update_total(deps.storage, &total)?;
+    try_store(deps.storage, &token_id, &nft_data, &minter, &cw721_contract, &config.schema)?;
+
+    let total = update_total(deps.storage, &Uint128::one())?;
 
     Ok(Response::new()
         .add_attribute("action", "store")
         .add_attribute("token_total", total.to_string())
+        .add_attribute("token_id", token_id)
     )
 }
 
@@ -540,11 +830,16 @@ pub fn execute_store_batch(
 
     let cw721_contract = CW721Contract::default();
     let minter = cw721_contract.minter.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
 
     let total = data.batch.len();
+    let mut index = config.token_total;
 
     data.batch.into_iter().try_for_each(|nft_data| {
-        try_store(deps.storage, &nft_data, &minter, &cw721_contract)
+        let token_id = config.token_id_scheme.token_id(index);
+        index += Uint128::one();
+
+        try_store(deps.storage, &token_id, &nft_data, &minter, &cw721_contract, &config.schema)
     })?;
 
     let batch_total = Uint128::from(total as u32);
@@ -579,43 +874,85 @@ pub fn execute_store_conf(
     // unwrap store_conf
     let store_data = store_conf;
 
+    check_store_conf(&store_data)?;
+
+    // compact tokens share one copy of the store conf, only saved when it changes
+    let mut version = STORE_CONF_VERSION.may_load(deps.storage)?;
+
+    if msg.compact {
+        let latest = match version {
+            Some(version) => STORE_CONFS.may_load(deps.storage, version)?,
+            None => None,
+        };
+
+        if latest.as_ref() != Some(&store_data) {
+            let next = version.map_or(0, |version| version + 1);
+
+            STORE_CONFS.save(deps.storage, next, &store_data)?;
+            STORE_CONF_VERSION.save(deps.storage, &next)?;
+
+            version = Some(next);
+        }
+    }
+
     let mut total = conf.token_total;
 
-    msg.attributes.into_iter().for_each(|item| {
-        let name = format!("{} #{}", store_data.name, total);
+    for (row, item) in msg.attributes.iter().enumerate() {
+        let token_id = conf.token_id_scheme.token_id(total);
 
-        let mut attr : Vec<Trait> = vec![];
-        for (index, value) in item.iter().enumerate() {
-            attr.push(Trait {
-                display_type: None,
-                value: value.clone(),
-                trait_type: store_data.attributes[index].clone()
-            })
+        let attr = expand_attributes(&store_data, row, item)?;
+
+        if contract.tokens.has(deps.storage, &token_id) {
+            return Err(ContractError::Exists {})
         }
 
-        let token = TokenInfo {
-            owner: minter.clone(),
-            approvals: vec![],
-            token_uri: None,
-            extension: Some(Metadata {
-                name: Some(name.clone()),
-                description: Some(store_data.desc.to_string()),
-                image: Some(format!("{}/{}.png", store_data.ipfs, total)),
-                attributes: Some(attr),
-                animation_url: None,
-                background_color: None,
-                image_data: None,
-                external_url: None,
-                youtube_url: None,
-            })
+        if let Some(schema) = &conf.schema {
+            let (token_uri, metadata) = build_store_conf_token(&store_data, &token_id, &total, attr.clone());
+            validate_metadata(schema, &token_id, &token_uri, &Some(metadata))?;
+        }
+
+        index_traits(deps.storage, &token_id, &attr)?;
+        track_token(deps.storage, &STORED_TOKENS, &STORED_COUNT, &token_id)?;
+
+        let token = match version {
+            Some(version) if msg.compact => {
+                COMPACT_TOKENS.save(deps.storage, &token_id, &CompactToken {
+                    conf: version,
+                    index: total,
+                    row: item.clone(),
+                })?;
+
+                TokenInfo {
+                    owner: minter.clone(),
+                    approvals: vec![],
+                    token_uri: None,
+                    extension: None,
+                }
+            },
+            _ => {
+                // the display name is decoupled from the storage key
+                let (token_uri, metadata) = build_store_conf_token(&store_data, &token_id, &total, attr);
+
+                TokenInfo {
+                    owner: minter.clone(),
+                    approvals: vec![],
+                    token_uri,
+                    extension: Some(metadata),
+                }
+            }
         };
 
-        contract.tokens.save(deps.storage, &name, &token).unwrap();
+        contract.tokens.save(deps.storage, &token_id, &token)?;
+
+        total = total.checked_add(Uint128::one()).map_err(|_| ContractError::CantUpdateTotal {})?;
+    }
 
-        total = total.checked_add(Uint128::one()).unwrap();
-    });
+    // stored tokens can not go above the token supply
+    if total > conf.token_supply {
+        return Err(ContractError::MaxTokenSupply {})
+    }
 
-    conf.token_total = conf.token_total.checked_add(total).unwrap();
+    conf.token_total = total;
 
     CONFIG.save(deps.storage, &conf)?;
 
@@ -624,3 +961,281 @@ pub fn execute_store_conf(
         .add_attribute("token_total", total.to_string())
     )
 }
+
+pub fn execute_update_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<UpdateMetadataMsg>,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+    check_unlocked(deps.storage, LockArea::Metadata)?;
+    check_batch_size(tokens.len(), CONFIG.load(deps.storage)?.limits.max_token_batch)?;
+
+    let contract = CW721Contract::default();
+
+    let events = tokens
+        .into_iter()
+        .map(|msg| try_update_metadata(deps.storage, &contract, msg))
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_metadata")
+        .add_attribute("updated", events.len().to_string())
+        .add_events(events)
+    )
+}
+
+pub fn execute_update_traits(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    set: Vec<Trait>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::TraitUpdater)?;
+    check_unlocked(deps.storage, LockArea::Metadata)?;
+
+    let contract = CW721Contract::default();
+
+    let events = try_update_traits(deps.storage, &contract, &token_id, set, remove)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_traits")
+        .add_attribute("token_id", token_id)
+        .add_attribute("changes", events.len().to_string())
+        .add_events(events)
+    )
+}
+
+pub fn execute_lock_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+    check_batch_size(tokens.len(), CONFIG.load(deps.storage)?.limits.max_token_batch)?;
+
+    let contract = CW721Contract::default();
+
+    for token_id in tokens.iter() {
+        if !contract.tokens.has(deps.storage, token_id) {
+            return Err(ContractError::TokenNotFound { token_id: token_id.clone() })
+        }
+
+        TOKEN_METADATA_LOCKS.save(deps.storage, token_id, &true)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "lock_metadata")
+        .add_attribute("tokens", format!("{:?}", tokens))
+    )
+}
+
+pub fn execute_set_svg_conf(
+    deps: DepsMut,
+    info: MessageInfo,
+    svg_conf: SvgConf,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+    check_unlocked(deps.storage, LockArea::Layers)?;
+
+    SVG_CONF.save(deps.storage, &svg_conf)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_svg_conf")
+        .add_attribute("layer_order", svg_conf.layer_order.join(","))
+    )
+}
+
+pub fn execute_upload_layers(
+    deps: DepsMut,
+    info: MessageInfo,
+    layers: Vec<SvgLayer>,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+    check_unlocked(deps.storage, LockArea::Layers)?;
+
+    check_batch_size(layers.len(), CONFIG.load(deps.storage)?.limits.max_layer_batch)?;
+
+    for layer in layers.iter() {
+        SVG_LAYERS.save(deps.storage, (layer.trait_type.as_str(), layer.value.as_str()), &layer.svg)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "upload_layers")
+        .add_attribute("layers", layers.len().to_string())
+    )
+}
+
+pub fn execute_compute_rarity(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+    recompute_on_burn: Option<bool>,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+
+    // scores only hold once traits can not change anymore
+    if !load_locks(deps.storage)?.is_locked(&LockArea::Metadata) {
+        return Err(ContractError::RarityMetadataUnlocked {})
+    }
+
+    let contract = CW721Contract::default();
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = RARITY_STATE.may_load(deps.storage)?.unwrap_or_default();
+
+    if let Some(recompute_on_burn) = recompute_on_burn {
+        state.recompute_on_burn = recompute_on_burn;
+    }
+
+    // a finished computation starts over
+    if state.done {
+        state.restart();
+    }
+
+    let limit = config.limits.rarity_batch(limit);
+    let mut scored = 0;
+    let mut ranked = 0;
+
+    if !state.scored {
+        let start = state.cursor.as_deref().map(Bound::exclusive);
+        // burnt and unstored tokens leave the trait index, token_total does not follow them
+        let total = Uint128::from(TRAITED_COUNT.may_load(deps.storage)?.unwrap_or_default());
+
+        let tokens = contract.tokens
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<String>>>()?;
+
+        for token_id in tokens.iter() {
+            let token = load_token(deps.storage, &contract, token_id)?;
+            let score = rarity_score(deps.storage, total, extension_traits(&token.extension))?;
+
+            save_rarity(deps.storage, token_id, score)?;
+        }
+
+        scored = tokens.len();
+
+        if scored < limit {
+            state.cursor = None;
+            state.scored = true;
+        } else {
+            state.cursor = tokens.last().cloned();
+        }
+    }
+
+    // ranks are written from the rarest token once every token is scored
+    if state.scored {
+        let end = state.rank_cursor
+            .as_ref()
+            .map(|(score, token_id)| Bound::exclusive((score.u128(), token_id.as_str())));
+
+        let batch = limit - scored;
+
+        // one more key tells if the ranking goes on
+        let mut ranking = RARITY_RANK
+            .keys(deps.storage, None, end, Order::Descending)
+            .take(batch + 1)
+            .collect::<StdResult<Vec<(u128, String)>>>()?;
+
+        let more = ranking.len() > batch;
+        ranking.truncate(batch);
+
+        for (_, token_id) in ranking.iter() {
+            state.ranked += 1;
+            RARITY_RANKS.save(deps.storage, token_id, &state.ranked)?;
+        }
+
+        ranked = ranking.len();
+
+        if more {
+            state.rank_cursor = ranking.last().map(|(score, token_id)| (Uint128::from(*score), token_id.clone()));
+        } else {
+            state.rank_cursor = None;
+            state.done = true;
+        }
+    }
+
+    RARITY_STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "compute_rarity")
+        .add_attribute("scored", scored.to_string())
+        .add_attribute("ranked", ranked.to_string())
+        .add_attribute("done", state.done.to_string())
+    )
+}
+
+pub fn execute_unstore(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+    check_unlocked(deps.storage, LockArea::Metadata)?;
+
+    let contract = CW721Contract::default();
+    let minter = contract.minter.load(deps.storage)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_batch_size(tokens.len(), config.limits.max_token_batch)?;
+
+    let new_total = config.token_total
+        .checked_sub(Uint128::from(tokens.len() as u128))
+        .map_err(|_| ContractError::CantUpdateTotal {})?;
+
+    // only the last stored ids can be removed so minting can keep walking the ids in order
+    let mut index = new_total;
+    while index < config.token_total {
+        let token_id = config.token_id_scheme.token_id(index);
+
+        if !tokens.contains(&token_id) {
+            return Err(ContractError::UnstoreNotLast { token_id })
+        }
+
+        index += Uint128::one();
+    }
+
+    for token_id in tokens.iter() {
+        try_unstore(deps.storage, &contract, &minter, token_id)?;
+    }
+
+    config.token_total = new_total;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unstore")
+        .add_attribute("tokens", format!("{:?}", tokens))
+        .add_attribute("token_total", config.token_total.to_string())
+    )
+}
+
+pub fn execute_replace_stored(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: UpdateMetadataMsg,
+) -> Result<Response, ContractError> {
+    // validate sender permissions
+    can_execute(&deps, &info, Role::MetadataManager)?;
+    check_unlocked(deps.storage, LockArea::Metadata)?;
+
+    let contract = CW721Contract::default();
+    let minter = contract.minter.load(deps.storage)?;
+
+    check_stored(&contract, deps.storage, &minter, &msg.token_id)?;
+
+    let event = try_update_metadata(deps.storage, &contract, msg)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "replace_stored")
+        .add_event(event)
+    )
+}
@@ -1,9 +1,14 @@
 use cosmwasm_std::{
-  DepsMut, MessageInfo, Coin, Uint128,
-  Storage, Addr, Timestamp,
-  Env, BlockInfo, StdError
+  Api, DepsMut, MessageInfo, Coin, Uint128,
+  Storage, Addr,
+  Env, BlockInfo, StdError, StdResult, Event,
+  Order, to_vec,
 };
 
+use sha2::{Digest, Sha256};
+
+use cw_storage_plus::{Item, Map};
+
 use cw721_base::{
   state::TokenInfo,
   MintMsg
@@ -11,6 +16,7 @@ use cw721_base::{
 
 use crate::{
   error::ContractError,
+  types_mint::{CollectionSchema, Dates, Limits, MintPhase},
   state::{
     CW721Contract,
     Extension,
@@ -19,12 +25,33 @@ use crate::{
     BURNT_AMOUNT,
     // BURNT_LIST,
     // BURNED,
-    Metadata, PLEDGED_TOKENS
-  }
+    Metadata, PLEDGED_TOKENS,
+    Role, ROLES,
+    PAUSE_WINDOWS, PAUSE_EXPIRES,
+    LockArea, Locks, LOCKS,
+    TOKEN_METADATA_LOCKS,
+    TRAIT_CHANGES,
+    Trait,
+    COMPACT_TOKENS, STORE_CONFS, STORE_CONF_VERSION,
+    TRAIT_TOKENS, TRAIT_COUNTS, TRAITED_TOKENS, TRAITED_COUNT,
+    RARITY, RARITY_RANK, RARITY_RANKS, RARITY_STATE,
+    SVG_LAYERS, SVG_CONF, SvgConf,
+    STORED_TOKENS, STORED_COUNT, MINTED_TOKENS, MINTED_COUNT,
+    HOLDER_BALANCES, TOKEN_OWNERS, STATS, SNAPSHOT_CLEAR,
+  },
+  msg::{StoreConf, UpdateMetadataMsg},
 };
 
+const DEFAULT_IMAGE_TEMPLATE: &str = "{ipfs}/{index}.{ext}";
+const DEFAULT_IMAGE_EXTENSION: &str = "png";
+const DEFAULT_ANIMATION_EXTENSION: &str = "mp4";
+
+// rarity scores keep six decimals
+pub const RARITY_PRECISION: u128 = 1_000_000;
+
 pub fn clear_state(
   storage: &mut dyn Storage,
+  height: u64,
 ) -> Result<(), ContractError> {
   let mut state_config = CONFIG.load(storage)?;
 
@@ -36,21 +63,279 @@ pub fn clear_state(
   // BURNT_LIST.clear(storage);
   BURNT_AMOUNT.clear(storage);
   PLEDGED_TOKENS.clear(storage);
+  STATS.remove(storage);
+
+  // compact tokens go with the tokens they rebuild
+  COMPACT_TOKENS.clear(storage);
+  STORE_CONFS.clear(storage);
+  STORE_CONF_VERSION.remove(storage);
+
+  // ids can be stored again, new tokens start unlocked
+  TOKEN_METADATA_LOCKS.clear(storage);
+  TRAIT_CHANGES.clear(storage);
+
+  // trait indexes and rarity only describe the cleared tokens
+  TRAIT_TOKENS.clear(storage);
+  TRAIT_COUNTS.clear(storage);
+  TRAITED_TOKENS.clear(storage);
+  TRAITED_COUNT.remove(storage);
+  RARITY.clear(storage);
+  RARITY_RANK.clear(storage);
+  RARITY_RANKS.clear(storage);
+  RARITY_STATE.remove(storage);
+
+  STORED_TOKENS.clear(storage);
+  STORED_COUNT.remove(storage);
+  MINTED_TOKENS.clear(storage);
+  MINTED_COUNT.remove(storage);
+
+  // snapshots that do not fit in one batch are left to clear_snapshots
+  let limit = state_config.limits.max_token_batch as usize;
+  clear_snapshots(storage, height, limit)?;
+
+  // keep the contract paused after clearing the state
+  PAUSE_EXPIRES.remove(storage);
 
   CONFIG.save(storage, &state_config)?;
 
   Ok(())
 }
 
-// Check if sender is the configured contract creator so they can update sensitive data
+// Check if an address holds a role, admins hold every role
+pub fn has_role(
+  storage: &dyn Storage,
+  address: &Addr,
+  role: &Role,
+) -> Result<bool, ContractError> {
+  // the configured creator is always an admin
+  if *address == CONFIG.load(storage)?.creator {
+    return Ok(true)
+  }
+
+  let roles = ROLES.may_load(storage, address)?.unwrap_or_default();
+
+  Ok(roles.contains(&Role::Admin) || roles.contains(role))
+}
+
+// Check if sender holds the role required by the message
+pub fn can_execute(
+  deps: &DepsMut,
+  info: &MessageInfo,
+  role: Role,
+) -> Result<(), ContractError> {
+  if !has_role(deps.storage, &info.sender, &role)? {
+    return Err(ContractError::MissingRole { role })
+  }
+
+  Ok(())
+}
+
+// Check if sender is an admin so they can update sensitive data
 pub fn can_update(
   deps: &DepsMut,
   info: &MessageInfo
 ) -> Result<(), ContractError> {
-  let creator = CONFIG.load(deps.storage)?.creator;
+  can_execute(deps, info, Role::Admin)
+}
+
+// Check the mint end date is after the mint start date
+pub fn check_dates(
+  dates: &Dates,
+) -> Result<(), ContractError> {
+  if let (Some(start), Some(end)) = (&dates.start, &dates.end) {
+    if end <= start {
+      return Err(ContractError::InvalidDates {})
+    }
+  }
+
+  Ok(())
+}
+
+// Validate a config before it is saved by instantiate, config updates and migrations
+pub fn validate_config(
+  api: &dyn Api,
+  config: &Config,
+) -> Result<(), ContractError> {
+  if api.addr_validate(&config.creator).is_err() {
+    return Err(ContractError::InvalidCreator { address: config.creator.clone() })
+  }
+
+  if api.addr_validate(config.wallet.wallet.as_str()).is_err() {
+    return Err(ContractError::InvalidWallet { address: config.wallet.wallet.to_string() })
+  }
+
+  if config.token_supply.is_zero() {
+    return Err(ContractError::SupplyZero {})
+  }
+
+  // stored tokens can not be above the supply
+  if config.token_supply < config.token_total {
+    return Err(ContractError::SupplyBelowTotal {
+      supply: config.token_supply,
+      total: config.token_total,
+    })
+  }
+
+  if config.cost.amount.is_zero() {
+    return Err(ContractError::CostZero {})
+  }
+
+  if config.cost.denom.is_empty() {
+    return Err(ContractError::CostDenomEmpty {})
+  }
+
+  if let Some(max_mint_batch) = config.max_mint_batch {
+    if max_mint_batch.is_zero() {
+      return Err(ContractError::MaxMintBatchZero {})
+    }
+
+    if max_mint_batch > config.token_supply {
+      return Err(ContractError::MaxMintBatchTooLarge {
+        max_mint_batch,
+        supply: config.token_supply,
+      })
+    }
+  }
+
+  // the store conf is checked where it changes, older confs have no values
+  check_limits(&config.limits)?;
+
+  check_dates(&config.dates)
+}
+
+pub fn check_limits(
+  limits: &Limits,
+) -> Result<(), ContractError> {
+  let sizes = [
+    limits.max_token_batch,
+    limits.max_query_batch,
+    limits.default_query_limit,
+    limits.max_query_limit,
+    limits.max_layer_batch,
+    limits.default_rarity_batch,
+    limits.max_rarity_batch,
+  ];
+
+  if sizes.contains(&0)
+    || limits.default_query_limit > limits.max_query_limit
+    || limits.default_rarity_batch > limits.max_rarity_batch {
+    return Err(ContractError::InvalidLimits {})
+  }
+
+  Ok(())
+}
+
+// Reject batches above the configured max
+pub fn check_batch_size(
+  size: usize,
+  max: u32,
+) -> Result<(), ContractError> {
+  if size > max as usize {
+    return Err(ContractError::RequestTooLarge { size, max })
+  }
+
+  Ok(())
+}
+
+// Every trait type needs its own list of values
+pub fn check_store_conf(
+  store_conf: &StoreConf,
+) -> Result<(), ContractError> {
+  if store_conf.attributes.len() != store_conf.values.len() {
+    return Err(ContractError::InvalidAttributeDictionary {
+      attributes: store_conf.attributes.len(),
+      values: store_conf.values.len(),
+    })
+  }
+
+  Ok(())
+}
+
+// Expand a row of value indexes into traits
+pub fn expand_attributes(
+  store_conf: &StoreConf,
+  row_index: usize,
+  row: &[u32],
+) -> Result<Vec<Trait>, ContractError> {
+  if row.len() != store_conf.attributes.len() {
+    return Err(ContractError::InvalidAttributeArity {
+      row: row_index,
+      expected: store_conf.attributes.len(),
+      got: row.len(),
+    })
+  }
+
+  row
+    .iter()
+    .zip(store_conf.attributes.iter().zip(store_conf.values.iter()))
+    .map(|(index, (trait_type, values))| {
+      let value = values.get(*index as usize).ok_or_else(|| ContractError::AttributeIndexOutOfBounds {
+        row: row_index,
+        trait_type: trait_type.clone(),
+        index: *index,
+      })?;
+
+      Ok(Trait {
+        display_type: None,
+        trait_type: trait_type.clone(),
+        value: value.clone(),
+      })
+    })
+    .collect()
+}
+
+// Load the current locks, a frozen contract has every area locked
+pub fn load_locks(
+  storage: &dyn Storage,
+) -> Result<Locks, ContractError> {
+  if CONFIG.load(storage)?.frozen {
+    return Ok(Locks::all())
+  }
+
+  Ok(LOCKS.may_load(storage)?.unwrap_or_default())
+}
+
+// Check if an area has been locked
+pub fn check_unlocked(
+  storage: &dyn Storage,
+  area: LockArea,
+) -> Result<(), ContractError> {
+  if load_locks(storage)?.is_locked(&area) {
+    return Err(ContractError::Locked { area })
+  }
+
+  Ok(())
+}
+
+// Check a config replacing the current one does not touch locked areas
+pub fn check_config_locks(
+  storage: &dyn Storage,
+  config: &Config,
+) -> Result<(), ContractError> {
+  let current = CONFIG.load(storage)?;
+
+  // freezing is final
+  if current.frozen && !config.frozen {
+    return Err(ContractError::ContractFrozen {})
+  }
+
+  if config.name != current.name
+    || config.store_conf != current.store_conf
+    || config.token_id_scheme != current.token_id_scheme
+    || config.schema != current.schema {
+    check_unlocked(storage, LockArea::Metadata)?;
+  }
+
+  if config.token_supply != current.token_supply {
+    check_unlocked(storage, LockArea::Supply)?;
+  }
+
+  if config.cost != current.cost {
+    check_unlocked(storage, LockArea::Price)?;
+  }
 
-  if info.sender != creator {
-      return Err(ContractError::Unauthorized {});
+  if config.wallet != current.wallet {
+    check_unlocked(storage, LockArea::Payout)?;
   }
 
   Ok(())
@@ -95,7 +380,7 @@ pub fn update_burnt_amount(
 
 pub fn check_token_exists_or_err(
   contract: &CW721Contract,
-  storage: &mut dyn Storage,
+  storage: &dyn Storage,
   token_id: &String
 ) -> Result<(), ContractError> {
   if !contract.tokens.has(storage, token_id) {
@@ -104,6 +389,33 @@ pub fn check_token_exists_or_err(
   Ok(())
 }
 
+// Check a token can be burnt by the sender in a batch
+pub fn check_burnable(
+  contract: &CW721Contract,
+  storage: &dyn Storage,
+  sender: &Addr,
+  is_burner: bool,
+  token_id: &String,
+) -> Result<TokenInfo<Extension>, ContractError> {
+  if !contract.tokens.has(storage, token_id) {
+    return Err(ContractError::DontExists { })
+  }
+
+  if !PLEDGED_TOKENS.has(storage, token_id.clone()) {
+    return Err(ContractError::TokenNotPledged { token_id: token_id.clone() })
+  }
+
+  let token = contract.tokens.load(storage, token_id.as_str())?;
+
+  if token.owner != *sender && !is_burner {
+    return Err(ContractError::UnauthorizedWithMsg {
+      msg: "sender is not owner".to_string()
+    })
+  }
+
+  Ok(token)
+}
+
 // burn a token
 pub fn burn_token(
   contract: &CW721Contract,
@@ -111,16 +423,41 @@ pub fn burn_token(
   token: &TokenInfo<Option<Metadata>>,
   token_id: &String,
   sender: &Addr,
+  block: &BlockInfo,
   check_owner: bool
 ) -> Result<(), ContractError> {
   if check_owner {
     check_token_ownership_basic(sender, token)?;
   }
 
+  let stored = load_token(storage, contract, token_id)?;
+  unindex_traits(storage, token_id, extension_traits(&stored.extension))?;
+
+  untrack_token(storage, &STORED_TOKENS, &STORED_COUNT, token_id)?;
+  untrack_token(storage, &MINTED_TOKENS, &MINTED_COUNT, token_id)?;
+
+  // the other scores are stale once the trait counts change
+  remove_rarity(storage, token_id)?;
+
+  if let Some(mut state) = RARITY_STATE.may_load(storage)? {
+    if state.recompute_on_burn {
+      state.restart();
+      RARITY_STATE.save(storage, &state)?;
+    }
+  }
+
   contract.tokens.remove(storage, token_id)?;
+  COMPACT_TOKENS.remove(storage, token_id);
 
   contract.decrement_tokens(storage)?;
 
+  move_holdings(storage, block.height, &[token_id.clone()], Some(&token.owner), None)?;
+
+  let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+  stats.burnt += 1;
+  stats.pledged = stats.pledged.saturating_sub(1);
+  STATS.save(storage, &stats)?;
+
   // BURNED.save(storage, token_id.clone(), &true)?;
 
   PLEDGED_TOKENS.save(storage, token_id.clone(), &true)?;
@@ -135,10 +472,10 @@ pub fn burn_and_update(
   token: &TokenInfo<Option<Metadata>>,
   token_id: &String,
   sender: &Addr,
-  _block: &BlockInfo,
+  block: &BlockInfo,
   check_owner: bool
 ) -> Result<(), ContractError> {
-  burn_token(contract, storage, token, token_id, sender, check_owner)?;
+  burn_token(contract, storage, token, token_id, sender, block, check_owner)?;
 
   update_burnt_amount(storage, sender)?;
 
@@ -153,8 +490,8 @@ pub fn can_store(
   deps: &DepsMut,
   info: &MessageInfo
 ) -> Result<(), ContractError> {
-  // call can update to verify rights
-  can_update(deps, info)?;
+  // verify rights to manage metadata
+  can_execute(deps, info, Role::MetadataManager)?;
 
   let config = CONFIG.load(deps.storage)?;
 
@@ -163,6 +500,9 @@ pub fn can_store(
     return Err(ContractError::ContractFrozen{})
   }
 
+  // check if metadata has been locked
+  check_unlocked(deps.storage, LockArea::Metadata)?;
+
   // check if token total is not above token supply
   if config.token_total >= config.token_supply {
       return Err(ContractError::MaxTokenSupply {});
@@ -215,42 +555,64 @@ pub fn can_pay(
   }
 }
 
-// Check if all conditions are meet and sender can mint
-pub fn can_mint(
+// Check if any scheduled pause window is active
+pub fn check_pause_windows(
+  storage: &dyn Storage,
+  block: &BlockInfo,
+) -> Result<(), ContractError> {
+  let windows = PAUSE_WINDOWS.may_load(storage)?.unwrap_or_default();
+
+  if windows.iter().any(|window| window.is_active(block)) {
+    return Err(ContractError::ContractPausedScheduled {})
+  }
+
+  Ok(())
+}
+
+// Check the manual pause, lifted once its expiry is triggered, and the scheduled windows
+pub fn check_paused(
+  storage: &dyn Storage,
+  config: &Config,
+  block: &BlockInfo,
+) -> Result<(), ContractError> {
+  if config.paused {
+    let expired = PAUSE_EXPIRES
+      .may_load(storage)?
+      .map_or(false, |expires| expires.is_triggered(block));
+
+    if !expired {
+      return Err(ContractError::ContractPaused{})
+    }
+  }
+
+  check_pause_windows(storage, block)
+}
+
+// Check if the sale is open, returns the current mint count
+pub fn check_mint_open(
+  storage: &dyn Storage,
+  block: &BlockInfo,
   count: &u64,
-  time: &Timestamp,
   config: &Config,
-  mint_amount: &Uint128,
-  minter: &Addr,
-  sender: &Addr
 ) -> Result<Uint128, ContractError> {
+  let time = &block.time;
+
   // check if contract is frozen
   if config.frozen {
     return Err(ContractError::ContractFrozen{})
   }
 
   // check if contract has been paused
-  if config.paused {
-    return Err(ContractError::ContractPaused{})
-  }
+  check_paused(storage, config, block)?;
+
+  // minted tokens can not be recorded until the snapshots are cleared
+  check_snapshots_cleared(storage)?;
 
   // check if contract contain token data
   if config.token_total == Uint128::from(0u32) {
       return Err(ContractError::CantMintNothing {});
   }
 
-  // check if mint amount is zero
-  if mint_amount.is_zero() {
-    return Err(ContractError::MintZero {  })
-  }
-
-  // validate max mint amount
-  if let Some(max_size) = &config.max_mint_batch {
-    if mint_amount > max_size {
-      return Err(ContractError::MintAmountLargerThanAllowed {  })
-    }
-  }
-
   // check if start_mint date is some and if is correct
   if let Some(stamp) = &config.dates.start {
     if time < stamp {
@@ -277,6 +639,33 @@ pub fn can_mint(
       return Err(ContractError::MaxTokens {});
   }
 
+  Ok(current_count)
+}
+
+// Check if all conditions are meet and sender can mint
+pub fn can_mint(
+  storage: &dyn Storage,
+  block: &BlockInfo,
+  count: &u64,
+  config: &Config,
+  mint_amount: &Uint128,
+  minter: &Addr,
+  sender: &Addr
+) -> Result<Uint128, ContractError> {
+  let current_count = check_mint_open(storage, block, count, config)?;
+
+  // check if mint amount is zero
+  if mint_amount.is_zero() {
+    return Err(ContractError::MintZero {  })
+  }
+
+  // validate max mint amount
+  if let Some(max_size) = &config.max_mint_batch {
+    if mint_amount > max_size {
+      return Err(ContractError::MintAmountLargerThanAllowed {  })
+    }
+  }
+
   // TODO: Review this
   // dont allow contract admin to become owner of tokens
   if sender == minter {
@@ -380,12 +769,16 @@ pub fn transfer_nft(
 
   // ensure we have permissions
   let mut token = check_token_ownership_complete(contract, storage, &env.block, &info.sender, token)?;
+  let previous_owner = token.owner.clone();
 
   // set owner and remove existing approvals
   token.owner = recipient.clone();
   token.approvals = vec![];
 
   contract.tokens.save(storage, token_id, &token)?;
+  // a stored token moved by the minter can not be minted anymore
+  untrack_token(storage, &STORED_TOKENS, &STORED_COUNT, token_id)?;
+  move_holdings(storage, env.block.height, &[token_id.clone()], Some(&previous_owner), Some(recipient))?;
 
   Ok(token_id.to_string())
 }
@@ -397,26 +790,46 @@ pub fn update_total(
 ) -> Result<Uint128, ContractError> {
   let mut config = CONFIG.load(storage)?;
 
-  let total = config.token_total.checked_add(*amount);
+  let total = config.token_total
+    .checked_add(*amount)
+    .map_err(|_| ContractError::CantUpdateTotal {})?;
 
-  if total.is_err() {
-    return Err(ContractError::CantUpdateTotal {})
+  // stored tokens can not go above the token supply
+  if total > config.token_supply {
+    return Err(ContractError::MaxTokenSupply {})
   }
 
-  config.token_total = total.unwrap();
+  config.token_total = total;
   CONFIG.save(storage, &config)?;
 
   Ok(config.token_total)
 }
 
-// Attempt to store a token's meta-data
+// Attempt to store a token's meta-data under the id given by the token id scheme
 pub fn try_store(
   storage: &mut dyn Storage,
+  token_id: &String,
   nft_data: &MintMsg<Extension>,
   minter: &Addr,
   contract: &CW721Contract,
+  schema: &Option<CollectionSchema>,
 ) -> Result<(), ContractError> {
-  let token_id = nft_data.token_id.clone();
+  // an empty id is left to the scheme, any other id must match it
+  if !nft_data.token_id.is_empty() && nft_data.token_id != *token_id {
+    return Err(ContractError::TokenIdMismatch {
+      token_id: nft_data.token_id.clone(),
+      expected: token_id.clone(),
+    })
+  }
+
+  // never overwrite a stored or minted token
+  if contract.tokens.has(storage, token_id) {
+    return Err(ContractError::Exists {})
+  }
+
+  if let Some(schema) = schema {
+    validate_metadata(schema, token_id, &nft_data.token_uri, &nft_data.extension)?;
+  }
 
   // create the token
   let token = TokenInfo {
@@ -426,31 +839,710 @@ pub fn try_store(
       extension: nft_data.extension.clone(),
   };
 
-  contract.tokens.save(storage, &token_id, &token)?;
+  contract.tokens.save(storage, token_id, &token)?;
+  // a stale compact entry would shadow the stored metadata
+  COMPACT_TOKENS.remove(storage, token_id);
+  index_traits(storage, token_id, extension_traits(&token.extension))?;
+  track_token(storage, &STORED_TOKENS, &STORED_COUNT, token_id)?;
 
   Ok(())
 }
 
-// Attempt to mint a token
-pub fn try_mint(
-  storage: &mut dyn Storage,
-  sender: &Addr,
+// Check a token is stored and still owned by the minter
+pub fn check_mintable(
+  storage: &dyn Storage,
   minter: &Addr,
   contract: &CW721Contract,
   token_id: &String
-) -> Result<(), ContractError> {
+) -> Result<TokenInfo<Extension>, ContractError> {
   check_token_exists_or_err(contract, storage, token_id)?;
 
-  let old_token = contract.tokens.load(storage, token_id)?;
+  let token = contract.tokens.load(storage, token_id)?;
 
-  if old_token.owner != minter.clone() {
+  if token.owner != minter.clone() {
     return Err(ContractError::Claimed {})
   }
 
+  Ok(token)
+}
+
+// Attempt to mint a token
+pub fn try_mint(
+  storage: &mut dyn Storage,
+  sender: &Addr,
+  minter: &Addr,
+  contract: &CW721Contract,
+  token_id: &String,
+) -> Result<(), ContractError> {
+  let old_token = check_mintable(storage, minter, contract, token_id)?;
+
   let mut new_token = old_token.clone();
   new_token.owner = sender.clone();
   contract.tokens.replace(storage, token_id, Some(&new_token), Some(&old_token))?;
   contract.increment_tokens(storage)?;
 
+  untrack_token(storage, &STORED_TOKENS, &STORED_COUNT, token_id)?;
+  track_token(storage, &MINTED_TOKENS, &MINTED_COUNT, token_id)?;
+
+  Ok(())
+}
+
+// Hash of a token's uri and metadata so indexers can tell when it changes
+pub fn metadata_hash(
+  token_uri: &Option<String>,
+  extension: &Extension,
+) -> Result<String, ContractError> {
+  let data = to_vec(&(token_uri, extension))?;
+
+  Ok(format!("{:x}", Sha256::digest(&data)))
+}
+
+// Attempt to replace a token's meta-data, returns the event for indexers
+pub fn try_update_metadata(
+  storage: &mut dyn Storage,
+  contract: &CW721Contract,
+  msg: UpdateMetadataMsg,
+) -> Result<Event, ContractError> {
+  let token_id = msg.token_id;
+
+  check_token_exists_or_err(contract, storage, &token_id)?;
+
+  if TOKEN_METADATA_LOCKS.has(storage, &token_id) {
+    return Err(ContractError::TokenMetadataLocked { token_id })
+  }
+
+  let mut token = load_token(storage, contract, &token_id)?;
+
+  let old_hash = metadata_hash(&token.token_uri, &token.extension)?;
+  let new_hash = metadata_hash(&msg.token_uri, &msg.extension)?;
+
+  if let Some(schema) = CONFIG.load(storage)?.schema {
+    validate_metadata(&schema, &token_id, &msg.token_uri, &msg.extension)?;
+  }
+
+  unindex_traits(storage, &token_id, extension_traits(&token.extension))?;
+  index_traits(storage, &token_id, extension_traits(&msg.extension))?;
+
+  token.token_uri = msg.token_uri;
+  token.extension = msg.extension;
+
+  contract.tokens.save(storage, &token_id, &token)?;
+  COMPACT_TOKENS.remove(storage, &token_id);
+
+  Ok(Event::new("update_metadata")
+    .add_attribute("token_id", token_id)
+    .add_attribute("old_hash", old_hash)
+    .add_attribute("new_hash", new_hash))
+}
+
+// Attempt to set and remove a token's traits, returns one event per change
+pub fn try_update_traits(
+  storage: &mut dyn Storage,
+  contract: &CW721Contract,
+  token_id: &String,
+  set: Vec<Trait>,
+  remove: Vec<String>,
+) -> Result<Vec<Event>, ContractError> {
+  check_token_exists_or_err(contract, storage, token_id)?;
+
+  if TOKEN_METADATA_LOCKS.has(storage, token_id) {
+    return Err(ContractError::TokenMetadataLocked { token_id: token_id.clone() })
+  }
+
+  let mut token = load_token(storage, contract, token_id)?;
+  unindex_traits(storage, token_id, extension_traits(&token.extension))?;
+
+  let mut metadata = token.extension.unwrap_or_default();
+  let mut attributes = metadata.attributes.unwrap_or_default();
+
+  let mut changes = TRAIT_CHANGES.may_load(storage, token_id)?.unwrap_or_default();
+  let mut events: Vec<Event> = vec![];
+
+  let mut add_event = |trait_type: &str, old: &str, new: &str| {
+    changes += 1;
+    events.push(Event::new("update_trait")
+      .add_attribute("token_id", token_id)
+      .add_attribute("trait_type", trait_type)
+      .add_attribute("old_value", old)
+      .add_attribute("new_value", new)
+      .add_attribute("change", changes.to_string()));
+  };
+
+  for item in set {
+    match attributes.iter_mut().find(|attr| attr.trait_type == item.trait_type) {
+      Some(attr) => {
+        if attr.value != item.value || attr.display_type != item.display_type {
+          add_event(&item.trait_type, &attr.value, &item.value);
+          *attr = item;
+        }
+      },
+      None => {
+        add_event(&item.trait_type, "", &item.value);
+        attributes.push(item);
+      }
+    }
+  }
+
+  for trait_type in remove {
+    if let Some(index) = attributes.iter().position(|attr| attr.trait_type == trait_type) {
+      let attr = attributes.remove(index);
+      add_event(&attr.trait_type, &attr.value, "");
+    }
+  }
+
+  metadata.attributes = Some(attributes);
+  token.extension = Some(metadata);
+
+  if let Some(schema) = CONFIG.load(storage)?.schema {
+    validate_metadata(&schema, token_id, &token.token_uri, &token.extension)?;
+  }
+
+  index_traits(storage, token_id, extension_traits(&token.extension))?;
+
+  contract.tokens.save(storage, token_id, &token)?;
+  COMPACT_TOKENS.remove(storage, token_id);
+  TRAIT_CHANGES.save(storage, token_id, &changes)?;
+
+  Ok(events)
+}
+
+// Check a token is still owned by the minter and its metadata is not locked
+pub fn check_stored(
+  contract: &CW721Contract,
+  storage: &mut dyn Storage,
+  minter: &Addr,
+  token_id: &String,
+) -> Result<TokenInfo<Extension>, ContractError> {
+  check_token_exists_or_err(contract, storage, token_id)?;
+
+  let token = load_token(storage, contract, token_id)?;
+
+  if token.owner != *minter {
+    return Err(ContractError::TokenMinted { token_id: token_id.clone() })
+  }
+
+  if TOKEN_METADATA_LOCKS.has(storage, token_id) {
+    return Err(ContractError::TokenMetadataLocked { token_id: token_id.clone() })
+  }
+
+  Ok(token)
+}
+
+// Attempt to remove a stored token
+pub fn try_unstore(
+  storage: &mut dyn Storage,
+  contract: &CW721Contract,
+  minter: &Addr,
+  token_id: &String,
+) -> Result<(), ContractError> {
+  let token = check_stored(contract, storage, minter, token_id)?;
+  unindex_traits(storage, token_id, extension_traits(&token.extension))?;
+
+  contract.tokens.remove(storage, token_id)?;
+  COMPACT_TOKENS.remove(storage, token_id);
+  TRAIT_CHANGES.remove(storage, token_id);
+  untrack_token(storage, &STORED_TOKENS, &STORED_COUNT, token_id)?;
+
+  Ok(())
+}
+
+// Replace the store conf placeholders of a template
+pub fn render_template(
+  template: &str,
+  store_conf: &StoreConf,
+  token_id: &str,
+  index: &Uint128,
+  ext: &str,
+  traits: &[Trait],
+) -> String {
+  let mut result = template
+    .replace("{id}", token_id)
+    .replace("{index}", &index.to_string())
+    .replace("{name}", &store_conf.name)
+    .replace("{ipfs}", &store_conf.ipfs)
+    .replace("{ext}", ext);
+
+  for item in traits {
+    result = result.replace(&format!("{{trait:{}}}", item.trait_type), &item.value);
+  }
+
+  result
+}
+
+// Build a token's uri and metadata from the store conf and its traits
+pub fn build_store_conf_token(
+  store_conf: &StoreConf,
+  token_id: &str,
+  index: &Uint128,
+  traits: Vec<Trait>,
+) -> (Option<String>, Metadata) {
+  let image_ext = store_conf.image_extension.as_deref().unwrap_or(DEFAULT_IMAGE_EXTENSION);
+  let animation_ext = store_conf.animation_extension.as_deref().unwrap_or(DEFAULT_ANIMATION_EXTENSION);
+
+  let image_template = store_conf.image.as_deref().unwrap_or(DEFAULT_IMAGE_TEMPLATE);
+
+  let render = |template: &str, ext: &str| {
+    render_template(template, store_conf, token_id, index, ext, &traits)
+  };
+
+  let token_uri = store_conf.token_uri.as_deref().map(|template| render(template, ""));
+
+  let metadata = Metadata {
+    name: Some(format!("{} #{}", store_conf.name, index)),
+    description: Some(store_conf.desc.to_string()),
+    image: Some(render(image_template, image_ext)),
+    animation_url: store_conf.animation_url.as_deref().map(|template| render(template, animation_ext)),
+    external_url: store_conf.external_url.as_deref().map(|template| render(template, "")),
+    attributes: Some(traits.clone()),
+    background_color: None,
+    image_data: None,
+    youtube_url: None,
+  };
+
+  (token_uri, metadata)
+}
+
+// Load a token, compact tokens get their uri and metadata rebuilt from the store conf
+pub fn load_token(
+  storage: &dyn Storage,
+  contract: &CW721Contract,
+  token_id: &str,
+) -> Result<TokenInfo<Extension>, ContractError> {
+  let mut token = contract.tokens.load(storage, token_id)?;
+
+  if let Some(compact) = COMPACT_TOKENS.may_load(storage, token_id)? {
+    let store_conf = STORE_CONFS.load(storage, compact.conf)?;
+    let traits = expand_attributes(&store_conf, 0, &compact.row)?;
+    let (token_uri, metadata) = build_store_conf_token(&store_conf, token_id, &compact.index, traits);
+
+    token.token_uri = token_uri;
+    token.extension = Some(metadata);
+  }
+
+  Ok(token)
+}
+
+// Traits of a token's metadata
+pub fn extension_traits(
+  extension: &Extension,
+) -> &[Trait] {
+  extension
+    .as_ref()
+    .and_then(|metadata| metadata.attributes.as_deref())
+    .unwrap_or(&[])
+}
+
+// Add a token's traits to the trait index
+pub fn index_traits(
+  storage: &mut dyn Storage,
+  token_id: &str,
+  traits: &[Trait],
+) -> Result<(), ContractError> {
+  for item in traits {
+    let key = (item.trait_type.as_str(), item.value.as_str(), token_id);
+
+    // a trait repeated in the same token is counted once
+    if TRAIT_TOKENS.has(storage, key) {
+      continue
+    }
+
+    TRAIT_TOKENS.save(storage, key, &true)?;
+    TRAIT_COUNTS.update(storage, (key.0, key.1), |count| -> StdResult<u64> {
+      Ok(count.unwrap_or_default() + 1)
+    })?;
+  }
+
+  if !traits.is_empty() {
+    track_token(storage, &TRAITED_TOKENS, &TRAITED_COUNT, token_id)?;
+  }
+
+  Ok(())
+}
+
+// Remove a token's traits from the trait index
+pub fn unindex_traits(
+  storage: &mut dyn Storage,
+  token_id: &str,
+  traits: &[Trait],
+) -> Result<(), ContractError> {
+  for item in traits {
+    let key = (item.trait_type.as_str(), item.value.as_str(), token_id);
+
+    if !TRAIT_TOKENS.has(storage, key) {
+      continue
+    }
+
+    TRAIT_TOKENS.remove(storage, key);
+
+    let count = TRAIT_COUNTS.may_load(storage, (key.0, key.1))?.unwrap_or_default().saturating_sub(1);
+
+    if count == 0 {
+      TRAIT_COUNTS.remove(storage, (key.0, key.1));
+    } else {
+      TRAIT_COUNTS.save(storage, (key.0, key.1), &count)?;
+    }
+  }
+
+  // every trait of the token is unindexed at once
+  untrack_token(storage, &TRAITED_TOKENS, &TRAITED_COUNT, token_id)?;
+
+  Ok(())
+}
+
+// Statistical rarity, the sum of total / count of every trait of the token
+pub fn rarity_score(
+  storage: &dyn Storage,
+  total: Uint128,
+  traits: &[Trait],
+) -> Result<Uint128, ContractError> {
+  let scaled_total = total.checked_mul(Uint128::from(RARITY_PRECISION)).map_err(StdError::from)?;
+  let mut score = Uint128::zero();
+
+  for item in traits {
+    let count = TRAIT_COUNTS
+      .may_load(storage, (item.trait_type.as_str(), item.value.as_str()))?
+      .unwrap_or_default();
+
+    if count == 0 {
+      continue
+    }
+
+    score = score.checked_add(scaled_total / Uint128::from(count)).map_err(StdError::from)?;
+  }
+
+  Ok(score)
+}
+
+// Save a token's rarity score and move it in the ranking
+// the stored rank is kept until the ranking step overwrites it
+pub fn save_rarity(
+  storage: &mut dyn Storage,
+  token_id: &str,
+  score: Uint128,
+) -> Result<(), ContractError> {
+  if let Some(old_score) = RARITY.may_load(storage, token_id)? {
+    RARITY_RANK.remove(storage, (old_score.u128(), token_id));
+  }
+
+  RARITY.save(storage, token_id, &score)?;
+  RARITY_RANK.save(storage, (score.u128(), token_id), &true)?;
+
+  Ok(())
+}
+
+// Remove a token's rarity score, ranking and rank
+pub fn remove_rarity(
+  storage: &mut dyn Storage,
+  token_id: &str,
+) -> Result<(), ContractError> {
+  if let Some(score) = RARITY.may_load(storage, token_id)? {
+    RARITY.remove(storage, token_id);
+    RARITY_RANK.remove(storage, (score.u128(), token_id));
+  }
+
+  RARITY_RANKS.remove(storage, token_id);
+
+  Ok(())
+}
+
+// Check a token's uri and metadata against the collection schema
+pub fn validate_metadata(
+  schema: &CollectionSchema,
+  token_id: &str,
+  token_uri: &Option<String>,
+  extension: &Extension,
+) -> Result<(), ContractError> {
+  let metadata = extension.clone().unwrap_or_default();
+
+  let fields = [
+    ("token_uri", token_uri),
+    ("name", &metadata.name),
+    ("description", &metadata.description),
+    ("image", &metadata.image),
+    ("image_data", &metadata.image_data),
+    ("external_url", &metadata.external_url),
+    ("background_color", &metadata.background_color),
+    ("animation_url", &metadata.animation_url),
+    ("youtube_url", &metadata.youtube_url),
+  ];
+
+  let urls = ["token_uri", "image", "external_url", "animation_url", "youtube_url"];
+
+  for field in schema.required_fields.iter() {
+    let is_set = match field.as_str() {
+      "attributes" => matches!(&metadata.attributes, Some(attributes) if !attributes.is_empty()),
+      _ => fields.iter().any(|(name, value)| *name == field.as_str() && value.is_some()),
+    };
+
+    if !is_set {
+      return Err(ContractError::MissingMetadataField { token_id: token_id.to_string(), field: field.clone() })
+    }
+  }
+
+  let check_length = |field: &str, value: &str| -> Result<(), ContractError> {
+    match schema.max_length {
+      Some(max) if value.chars().count() > max as usize => Err(ContractError::FieldTooLong {
+        token_id: token_id.to_string(),
+        field: field.to_string(),
+        max,
+      }),
+      _ => Ok(()),
+    }
+  };
+
+  for (field, value) in fields.iter() {
+    if let Some(value) = value {
+      check_length(field, value)?;
+
+      if urls.contains(field) && !schema.accepts_url(value) {
+        return Err(ContractError::InvalidUrlScheme {
+          token_id: token_id.to_string(),
+          field: field.to_string(),
+          url: value.clone(),
+        })
+      }
+    }
+  }
+
+  let traits = metadata.attributes.unwrap_or_default();
+
+  for item in traits.iter() {
+    check_length(&item.trait_type, &item.value)?;
+
+    if schema.traits.is_empty() {
+      continue
+    }
+
+    match schema.traits.iter().find(|rule| rule.trait_type == item.trait_type) {
+      Some(rule) if !rule.accepts(&item.value) => {
+        return Err(ContractError::InvalidTraitValue {
+          token_id: token_id.to_string(),
+          trait_type: item.trait_type.clone(),
+          value: item.value.clone(),
+        })
+      },
+      Some(_) => {},
+      None => {
+        return Err(ContractError::UnknownTraitType {
+          token_id: token_id.to_string(),
+          trait_type: item.trait_type.clone(),
+        })
+      }
+    }
+  }
+
+  for rule in schema.traits.iter().filter(|rule| rule.required) {
+    if !traits.iter().any(|item| item.trait_type == rule.trait_type) {
+      return Err(ContractError::MissingTrait {
+        token_id: token_id.to_string(),
+        trait_type: rule.trait_type.clone(),
+      })
+    }
+  }
+
+  Ok(())
+}
+
+// Composite the svg layers of a token's traits in the configured order
+pub fn render_svg(
+  storage: &dyn Storage,
+  svg_conf: &SvgConf,
+  traits: &[Trait],
+) -> Result<String, ContractError> {
+  let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{}\">", svg_conf.view_box);
+
+  for trait_type in svg_conf.layer_order.iter() {
+    if let Some(item) = traits.iter().find(|item| item.trait_type == *trait_type) {
+      if let Some(layer) = SVG_LAYERS.may_load(storage, (trait_type.as_str(), item.value.as_str()))? {
+        svg.push_str(&layer);
+      }
+    }
+  }
+
+  svg.push_str("</svg>");
+
+  Ok(svg)
+}
+
+// Load a token as returned by queries, with its image generated when svg layers are on
+pub fn render_token(
+  storage: &dyn Storage,
+  contract: &CW721Contract,
+  token_id: &str,
+) -> Result<TokenInfo<Extension>, ContractError> {
+  let mut token = load_token(storage, contract, token_id)?;
+
+  if let Some(svg_conf) = SVG_CONF.may_load(storage)? {
+    let mut metadata = token.extension.unwrap_or_default();
+    let traits = metadata.attributes.clone().unwrap_or_default();
+
+    metadata.image_data = Some(render_svg(storage, &svg_conf, &traits)?);
+    token.extension = Some(metadata);
+  }
+
+  Ok(token)
+}
+
+// Add a token to the stored or minted list and bump its count
+pub fn track_token(
+  storage: &mut dyn Storage,
+  list: &Map<&str, bool>,
+  count: &Item<u64>,
+  token_id: &str,
+) -> Result<(), ContractError> {
+  if !list.has(storage, token_id) {
+    list.save(storage, token_id, &true)?;
+    count.save(storage, &(count.may_load(storage)?.unwrap_or_default() + 1))?;
+  }
+
+  Ok(())
+}
+
+// Remove a token from the stored or minted list and lower its count
+pub fn untrack_token(
+  storage: &mut dyn Storage,
+  list: &Map<&str, bool>,
+  count: &Item<u64>,
+  token_id: &str,
+) -> Result<(), ContractError> {
+  if list.has(storage, token_id) {
+    list.remove(storage, token_id);
+    count.save(storage, &count.may_load(storage)?.unwrap_or_default().saturating_sub(1))?;
+  }
+
+  Ok(())
+}
+
+// Holdings can not move while a clear state migration leaves snapshots behind
+pub fn check_snapshots_cleared(
+  storage: &dyn Storage,
+) -> Result<(), ContractError> {
+  if SNAPSHOT_CLEAR.may_load(storage)?.unwrap_or_default() {
+    return Err(ContractError::SnapshotsNotCleared {})
+  }
+
+  Ok(())
+}
+
+// Remove up to limit snapshot entries at height, returns the removed count and if none are left
+// snapshot maps keep a changelog per removed key, so they can not be cleared at once
+pub fn clear_snapshots(
+  storage: &mut dyn Storage,
+  height: u64,
+  limit: usize,
+) -> Result<(usize, bool), ContractError> {
+  let holders = HOLDER_BALANCES
+    .keys(storage, None, None, Order::Ascending)
+    .take(limit)
+    .collect::<StdResult<Vec<_>>>()?;
+
+  for addr in holders.iter() {
+    HOLDER_BALANCES.remove(storage, addr, height)?;
+  }
+
+  let tokens = TOKEN_OWNERS
+    .keys(storage, None, None, Order::Ascending)
+    .take(limit - holders.len())
+    .collect::<StdResult<Vec<_>>>()?;
+
+  for token_id in tokens.iter() {
+    TOKEN_OWNERS.remove(storage, token_id, height)?;
+  }
+
+  let done = HOLDER_BALANCES.keys(storage, None, None, Order::Ascending).next().is_none()
+    && TOKEN_OWNERS.keys(storage, None, None, Order::Ascending).next().is_none();
+
+  if done {
+    SNAPSHOT_CLEAR.remove(storage);
+  } else {
+    SNAPSHOT_CLEAR.save(storage, &true)?;
+  }
+
+  Ok((holders.len() + tokens.len(), done))
+}
+
+// Move tokens between owners, none when they are burnt
+// tokens held by the minter are stored inventory, not holdings
+// owners and balances are snapshotted at the given height
+pub fn move_holdings(
+  storage: &mut dyn Storage,
+  height: u64,
+  token_ids: &[String],
+  from: Option<&Addr>,
+  to: Option<&Addr>,
+) -> Result<(), ContractError> {
+  let minter = CW721Contract::default().minter.load(storage)?;
+  let from = from.filter(|addr| **addr != minter);
+  let to = to.filter(|addr| **addr != minter);
+
+  if from == to || token_ids.is_empty() {
+    return Ok(())
+  }
+
+  check_snapshots_cleared(storage)?;
+
+  for token_id in token_ids.iter() {
+    match to {
+      Some(addr) => TOKEN_OWNERS.save(storage, token_id, addr, height)?,
+      None => TOKEN_OWNERS.remove(storage, token_id, height)?,
+    }
+  }
+
+  let amount = token_ids.len() as u64;
+  let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+
+  if let Some(addr) = from {
+    let balance = HOLDER_BALANCES.may_load(storage, addr)?.unwrap_or_default();
+    let new_balance = balance.saturating_sub(amount);
+
+    if new_balance == 0 {
+      HOLDER_BALANCES.remove(storage, addr, height)?;
+    } else {
+      HOLDER_BALANCES.save(storage, addr, &new_balance, height)?;
+    }
+
+    stats.update_holder(balance, new_balance);
+  }
+
+  if let Some(addr) = to {
+    let balance = HOLDER_BALANCES.may_load(storage, addr)?.unwrap_or_default();
+
+    HOLDER_BALANCES.save(storage, addr, &(balance + amount), height)?;
+
+    stats.update_holder(balance, balance + amount);
+  }
+
+  STATS.save(storage, &stats)?;
+
+  Ok(())
+}
+
+// Add mint funds to the stats
+pub fn record_revenue(
+  storage: &mut dyn Storage,
+  paid: &Coin,
+) -> Result<(), ContractError> {
+  let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+
+  stats.add_revenue(paid);
+
+  STATS.save(storage, &stats)?;
+
+  Ok(())
+}
+
+// Add minted tokens to the stats of the current sale phase
+pub fn record_mints(
+  storage: &mut dyn Storage,
+  phase: MintPhase,
+  dates: &Dates,
+  count: u64,
+) -> Result<(), ContractError> {
+  let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+
+  stats.add_mints(phase, dates, count);
+
+  STATS.save(storage, &stats)?;
+
   Ok(())
 }
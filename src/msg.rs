@@ -1,8 +1,19 @@
-use cosmwasm_schema::cw_serde;
-
-use cw721::Expiration;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use cw721::{
+    AllNftInfoResponse,
+    Approval,
+    ContractInfoResponse,
+    Expiration,
+    NftInfoResponse,
+    NumTokensResponse,
+    OperatorsResponse,
+    OwnerOfResponse,
+    TokensResponse,
+};
+use cw_utils::Scheduled;
 
-use cosmwasm_std::{Addr, Binary, Uint128, Empty};
+use cosmwasm_std::{Addr, Binary, Coin, Uint128, Empty, Timestamp};
 
 use cw721_base::{
     msg::{
@@ -14,7 +25,7 @@ use cw721_base::{
 };
 
 use crate::{
-    state::Extension,
+    state::{Config, Extension, LockArea, Locks, PhaseMints, Role, SvgConf, SvgLayer, Trait},
     types_mint,
 };
 
@@ -26,6 +37,13 @@ pub struct BatchStoreMsg {
     pub batch: Vec<CW721MintMsg<Extension>>
 }
 
+#[cw_serde]
+pub struct UpdateMetadataMsg {
+    pub token_id: String,
+    pub token_uri: Option<String>,
+    pub extension: Extension,
+}
+
 #[cw_serde]
 pub struct TransferOperation {
     pub recipient: String,
@@ -37,12 +55,32 @@ pub struct MintBatchMsg {
     pub amount: Uint128
 }
 
+// Templates support the placeholders
+// {id} storage token id, {index} position in the collection, {name} collection name,
+// {ipfs} ipfs prefix, {ext} file extension of the field and {trait:<trait_type>} row values
 #[cw_serde]
 pub struct StoreConf {
     pub name: String,
     pub desc: String,
     pub ipfs: String,
+    // trait types, one per position in a stored row
     pub attributes: Vec<String>,
+
+    // values[n] lists the possible values of attributes[n]
+    #[serde(default)]
+    pub values: Vec<Vec<String>>,
+
+    // defaults to "{ipfs}/{index}.{ext}"
+    pub image: Option<String>,
+    // defaults to "png"
+    pub image_extension: Option<String>,
+
+    pub animation_url: Option<String>,
+    // defaults to "mp4"
+    pub animation_extension: Option<String>,
+
+    pub external_url: Option<String>,
+    pub token_uri: Option<String>,
 }
 
 impl Default for StoreConf {
@@ -52,14 +90,25 @@ impl Default for StoreConf {
             desc: String::from("desc"),
             ipfs: String::from("ipfs://"),
             attributes: vec![],
+            values: vec![],
+            image: None,
+            image_extension: None,
+            animation_url: None,
+            animation_extension: None,
+            external_url: None,
+            token_uri: None,
         }
     }
 }
 
 #[cw_serde]
 pub struct StoreConfMsg {
-    pub attributes: Vec<Vec<String>>,
-    pub conf: Option<StoreConf>
+    // one row per token, each entry is an index into the matching store conf values
+    pub attributes: Vec<Vec<u32>>,
+    pub conf: Option<StoreConf>,
+    // keep only the trait indexes per token and share the store conf
+    #[serde(default)]
+    pub compact: bool,
 }
 
 #[cw_serde]
@@ -91,6 +140,18 @@ pub struct InstantiateMsg {
 
     // Used for StoreConf call but can be provided during the call
     pub store_conf: StoreConf,
+
+    // Token ids used by every store path and by minting, defaults to plain indexes
+    #[serde(default)]
+    pub token_id_scheme: types_mint::TokenIdScheme,
+
+    // Metadata rules enforced by every store and metadata update
+    #[serde(default)]
+    pub schema: Option<types_mint::CollectionSchema>,
+
+    // Max sizes per operation, defaults to 30 tokens per batch
+    #[serde(default)]
+    pub limits: types_mint::Limits,
 }
 
 impl InstantiateMsg {
@@ -106,10 +167,44 @@ impl InstantiateMsg {
             wallet: Default::default(),
             max_mint_batch: Default::default(),
             store_conf: Default::default(),
+            token_id_scheme: Default::default(),
+            schema: None,
+            limits: Default::default(),
         }
     }
 }
 
+// Patch style config update, fields left as None keep their current value
+#[cw_serde]
+#[derive(Default)]
+pub struct UpdateConfigMsg {
+    pub name: Option<String>,
+
+    // can not be lower than the amount of stored tokens
+    pub token_supply: Option<Uint128>,
+
+    // payout field, requires the treasurer role
+    pub cost: Option<types_mint::Costs>,
+
+    pub dates: Option<types_mint::Dates>,
+
+    pub max_mint_batch: Option<Uint128>,
+
+    pub burn: Option<types_mint::Burn>,
+
+    // payout field, requires the treasurer role
+    pub wallet: Option<types_mint::Wallet>,
+
+    pub store_conf: Option<StoreConf>,
+
+    // can only change while nothing is stored
+    pub token_id_scheme: Option<types_mint::TokenIdScheme>,
+
+    pub schema: Option<types_mint::CollectionSchema>,
+
+    pub limits: Option<types_mint::Limits>,
+}
+
 impl From<InstantiateMsg> for CW721InstantiateMsg {
     fn from(msg: InstantiateMsg) -> CW721InstantiateMsg {
         CW721InstantiateMsg {
@@ -122,20 +217,42 @@ impl From<InstantiateMsg> for CW721InstantiateMsg {
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    // freeze contract
+    // freeze contract, locks every area for good
     Freeze(),
 
-    // unfreeze contract
-    Unfreeze(),
+    // lock the given areas for good
+    Lock {
+        areas: Vec<LockArea>,
+    },
 
-    // pause contract
-    Pause(),
+    // pause contract, optionally unpause automatically once until is triggered
+    Pause {
+        until: Option<Scheduled>,
+    },
 
     // unpause contract
     Unpause(),
 
-    // update the initial config
-    UpdateConf (InstantiateMsg),
+    // schedule a pause window respected by mint, burn and transfer
+    SchedulePause(types_mint::PauseWindow),
+
+    // remove all scheduled pause windows
+    ClearPauseSchedule {},
+
+    // update the config, only the given fields are changed
+    UpdateConfig(UpdateConfigMsg),
+
+    // grant a role to an address, admin only
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+
+    // revoke a role from an address, admin only
+    RevokeRole {
+        address: String,
+        role: Role,
+    },
 
     // add token to pledge list
     Pledge {
@@ -170,6 +287,53 @@ pub enum ExecuteMsg {
     // Optimized batch token metadata storage
     StoreConf(StoreConfMsg),
 
+    // Remove stored tokens that have not been minted yet
+    Unstore {
+        tokens: Vec<String>,
+    },
+
+    // Replace the data of a stored token that has not been minted yet
+    ReplaceStored(UpdateMetadataMsg),
+
+    // Replace a stored or minted token metadata
+    UpdateMetadata(UpdateMetadataMsg),
+
+    // Replace the metadata of many tokens
+    UpdateMetadataBatch {
+        tokens: Vec<UpdateMetadataMsg>,
+    },
+
+    // Set or remove token traits, callable by trait updaters
+    UpdateTraits {
+        token_id: String,
+        set: Vec<Trait>,
+        remove: Vec<String>,
+    },
+
+    // Lock the metadata of the given tokens for good
+    LockMetadata {
+        tokens: Vec<String>,
+    },
+
+    // Generate token images from svg layers
+    SetSvgConf(SvgConf),
+
+    // Upload svg layer fragments, replaces existing layers
+    UploadLayers {
+        layers: Vec<SvgLayer>,
+    },
+
+    // Score token rarity in batches once metadata is locked
+    ComputeRarity {
+        limit: Option<u32>,
+        recompute_on_burn: Option<bool>,
+    },
+
+    // Remove owner and balance snapshots in batches after a clear state migration
+    ClearSnapshots {
+        limit: Option<u32>,
+    },
+
     // Standard CW721 ExecuteMsg
     // Transfer is a base message to move a token to another account without triggering actions
     TransferNft {
@@ -235,58 +399,183 @@ impl From<ExecuteMsg> for CW721ExecuteMsg<Extension, Empty> {
 }
 
 #[cw_serde]
-// #[derive(QueryResponses)]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
     // Returns the current contract config
+    #[returns(Config)]
     Config {},
 
     // Standard cw721 queries
+    #[returns(OwnerOfResponse)]
     OwnerOf {
         token_id: String,
         include_expired: Option<bool>,
     },
+    #[returns(OperatorsResponse)]
     ApprovedForAll {
         owner: String,
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    #[returns(NumTokensResponse)]
     NumTokens {},
+    #[returns(ContractInfoResponse)]
     ContractInfo {},
+    #[returns(NftInfoResponse<Extension>)]
     NftInfo {
         token_id: String,
     },
+    #[returns(AllNftInfoResponse<Extension>)]
     AllNftInfo {
         token_id: String,
         include_expired: Option<bool>,
     },
+    #[returns(TokensResponse)]
     Tokens {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    #[returns(TokensResponse)]
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    // Returns one entry per requested token, in order, paged after start_after
+    #[returns(NftInfoBatchResponse)]
     NftInfoBatch {
         tokens: Vec<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Same as NftInfoBatch with the owner and approvals of AllNftInfo
+    #[returns(AllNftInfoBatchResponse)]
+    AllNftInfoBatch {
+        tokens: Vec<String>,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
+    #[returns(Option<Uint128>)]
     BurntAmount {
         address: Addr
     },
-    BurntList {
-        address: Addr
+    // Returns the roles held by an address
+    #[returns(RolesResponse)]
+    Roles {
+        address: String,
+    },
+    // Returns the manual pause state and the scheduled pause windows
+    #[returns(PauseScheduleResponse)]
+    PauseSchedule {},
+    // Returns the locked areas
+    #[returns(Locks)]
+    Locks {},
+    // Returns the amount of trait changes of a token
+    #[returns(u64)]
+    TraitChanges {
+        token_id: String,
+    },
+    // Returns the tokens holding a trait value
+    #[returns(TokensResponse)]
+    TokensByTrait {
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
-    Burned {
+    // Returns the amount of tokens per value of a trait type
+    #[returns(TraitCountsResponse)]
+    TraitCounts {
+        trait_type: String,
+    },
+    // Returns the tokens still owned by the minter
+    #[returns(TokensResponse)]
+    StoredTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns the tokens claimed by a mint
+    #[returns(TokensResponse)]
+    MintedTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns the amount of stored and minted tokens
+    #[returns(TokenCountsResponse)]
+    TokenCounts {},
+    // Returns the sale state and why a mint would fail right now
+    #[returns(MintStatusResponse)]
+    MintStatus {},
+    // Runs the mint checks without minting
+    #[returns(SimulateMintResponse)]
+    SimulateMint {
+        sender: String,
+        amount: Uint128,
+        funds: Vec<Coin>,
+    },
+    // Runs the burn batch checks without burning
+    #[returns(SimulateBurnResponse)]
+    SimulateBurn {
+        sender: String,
         tokens: Vec<String>,
     },
+    // Returns the svg conf, none when images are not generated
+    #[returns(Option<SvgConf>)]
+    SvgConf {},
+    // Returns the svg fragment of a trait value
+    #[returns(Option<String>)]
+    SvgLayer {
+        trait_type: String,
+        value: String,
+    },
+    // Returns the rarity score and rank of a token
+    #[returns(RarityResponse)]
+    Rarity {
+        token_id: String,
+    },
+    // Returns the tokens from rarest to most common
+    #[returns(RarityRankResponse)]
+    RarityRank {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns the holder, burn, pledge, revenue and mint stats
+    #[returns(StatsResponse)]
+    Stats {},
+    // Returns the amount of tokens owned by an address
+    #[returns(BalanceResponse)]
+    Balance {
+        owner: String,
+    },
+    // Returns the owners and their balances, the minter is left out
+    #[returns(HoldersResponse)]
+    Holders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Returns the owner of a token at the start of the block at height
+    #[returns(OwnerOfAtHeightResponse)]
+    OwnerOfAtHeight {
+        token_id: String,
+        height: u64,
+    },
+    // Returns the amount of tokens owned by an address at the start of the block at height
+    #[returns(BalanceAtHeightResponse)]
+    BalanceAtHeight {
+        owner: String,
+        height: u64,
+    },
 }
 
 impl From<QueryMsg> for CW721QueryMsg<Empty> {
     fn from(msg: QueryMsg) -> CW721QueryMsg<Empty> {
         match msg {
             QueryMsg::OwnerOf { token_id, include_expired, } => CW721QueryMsg::OwnerOf { token_id, include_expired, },
+            QueryMsg::ApprovedForAll { owner, include_expired, start_after, limit } => {
+                CW721QueryMsg::AllOperators { owner, include_expired, start_after, limit }
+            },
             QueryMsg::NumTokens {} => CW721QueryMsg::NumTokens {},
             QueryMsg::ContractInfo {} => CW721QueryMsg::ContractInfo {},
             QueryMsg::NftInfo { token_id } => CW721QueryMsg::NftInfo { token_id },
@@ -298,6 +587,189 @@ impl From<QueryMsg> for CW721QueryMsg<Empty> {
     }
 }
 
+#[cw_serde]
+pub struct PauseScheduleResponse {
+    // paused right now, either manually or by a window
+    pub paused: bool,
+    // expiry of the manual pause
+    pub paused_until: Option<Scheduled>,
+    pub windows: Vec<types_mint::PauseWindow>,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub address: String,
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct TraitCount {
+    pub value: String,
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct MintStatusResponse {
+    pub open: bool,
+    pub reason: Option<types_mint::MintBlockedReason>,
+    pub phase: types_mint::MintPhase,
+    pub start: Option<Timestamp>,
+    pub end: Option<Timestamp>,
+    // stored tokens
+    pub token_total: Uint128,
+    pub token_supply: Uint128,
+    // same count as token_counts
+    pub minted: u64,
+    // stored tokens left to mint
+    pub remaining: Uint128,
+    pub price: types_mint::Costs,
+    pub max_mint_batch: Option<Uint128>,
+    pub paused: bool,
+    pub paused_until: Option<Scheduled>,
+    pub frozen: bool,
+}
+
+#[cw_serde]
+pub enum TokenStatus {
+    Found,
+    NotFound,
+    Burnt,
+}
+
+// Stored token info, approvals are not filtered
+#[cw_serde]
+pub struct BatchTokenInfo {
+    pub owner: Addr,
+    pub approvals: Vec<Approval>,
+    pub token_uri: Option<String>,
+    pub extension: Extension,
+}
+
+#[cw_serde]
+pub struct NftInfoBatchItem {
+    pub token_id: String,
+    pub status: TokenStatus,
+    // only set when the token is found
+    pub info: Option<BatchTokenInfo>,
+}
+
+#[cw_serde]
+pub struct NftInfoBatchResponse {
+    pub tokens: Vec<NftInfoBatchItem>,
+}
+
+#[cw_serde]
+pub struct AllNftInfoBatchItem {
+    pub token_id: String,
+    pub status: TokenStatus,
+    pub info: Option<AllNftInfoResponse<Extension>>,
+}
+
+#[cw_serde]
+pub struct AllNftInfoBatchResponse {
+    pub tokens: Vec<AllNftInfoBatchItem>,
+}
+
+#[cw_serde]
+pub struct TokenCountsResponse {
+    pub stored: u64,
+    pub minted: u64,
+}
+
+#[cw_serde]
+pub struct SimulateMintResponse {
+    // token ids the mint would assign
+    pub tokens: Vec<String>,
+    pub cost: Option<Coin>,
+    // the error the mint would fail with
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct SimulateBurnResponse {
+    pub tokens: Vec<String>,
+    // the error the burn would fail with
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct RarityResponse {
+    pub token_id: String,
+    pub score: Uint128,
+    // position from the rarest token, none until a computation ranks the token
+    pub rank: Option<u64>,
+    // scores are being recomputed
+    pub stale: bool,
+}
+
+#[cw_serde]
+pub struct TokenRarity {
+    pub token_id: String,
+    pub score: Uint128,
+}
+
+#[cw_serde]
+pub struct RarityRankResponse {
+    pub tokens: Vec<TokenRarity>,
+    pub stale: bool,
+}
+
+#[cw_serde]
+pub struct HolderBucket {
+    pub min: u64,
+    // none for the last bucket
+    pub max: Option<u64>,
+    pub holders: u64,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub holders: u64,
+    pub buckets: Vec<HolderBucket>,
+    pub burnt: u64,
+    pub pledged: u64,
+    pub revenue: Vec<Coin>,
+    pub mints: Vec<PhaseMints>,
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub owner: String,
+    pub balance: u64,
+}
+
+#[cw_serde]
+pub struct Holder {
+    pub address: Addr,
+    pub balance: u64,
+}
+
+#[cw_serde]
+pub struct HoldersResponse {
+    pub holders: Vec<Holder>,
+}
+
+#[cw_serde]
+pub struct OwnerOfAtHeightResponse {
+    pub token_id: String,
+    pub height: u64,
+    // none when the token was not minted yet, held by the minter or already burnt
+    pub owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct BalanceAtHeightResponse {
+    pub owner: String,
+    pub height: u64,
+    pub balance: u64,
+}
+
+#[cw_serde]
+pub struct TraitCountsResponse {
+    pub trait_type: String,
+    pub counts: Vec<TraitCount>,
+}
+
 // #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 // #[serde(rename_all = "snake_case")]
 #[cw_serde]
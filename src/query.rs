@@ -3,10 +3,12 @@
 use cosmwasm_std::{
     Uint128,
     Addr,
+    Coin,
+    MessageInfo,
     Deps,
     StdResult,
     StdError,
-    // Env
+    Env,
 };
 
 use crate::state::{
@@ -14,12 +16,86 @@ use crate::state::{
     CW721Contract
 };
 
-use cw721_base::state::{ TokenInfo };
+use crate::msg::{
+    PauseScheduleResponse,
+    RolesResponse,
+    TraitCount,
+    TraitCountsResponse,
+    RarityResponse,
+    MintStatusResponse,
+    TokenCountsResponse,
+    SimulateMintResponse,
+    SimulateBurnResponse,
+    RarityRankResponse,
+    TokenRarity,
+    TokenStatus,
+    BatchTokenInfo,
+    NftInfoBatchItem,
+    NftInfoBatchResponse,
+    AllNftInfoBatchItem,
+    AllNftInfoBatchResponse,
+    HolderBucket,
+    StatsResponse,
+    BalanceResponse,
+    Holder,
+    HoldersResponse,
+    OwnerOfAtHeightResponse,
+    BalanceAtHeightResponse,
+};
+
+use cosmwasm_std::Order;
+use cw_storage_plus::{Bound, Map};
+
+use cw721::{AllNftInfoResponse, Approval, Cw721Query, NftInfoResponse, TokensResponse};
+
+use crate::error::ContractError;
+
+use crate::helpers::{
+    check_paused,
+    check_mint_open,
+    check_pause_windows,
+    check_mintable,
+    check_burnable,
+    check_batch_size,
+    check_snapshots_cleared,
+    can_mint,
+    can_pay,
+    has_role,
+    load_locks,
+    render_token,
+};
+
+use crate::types_mint::MintBlockedReason;
 
 use crate::state::{
     CONFIG,
     Config,
+    Role,
+    ROLES,
+    Locks,
+    TRAIT_CHANGES,
+    COMPACT_TOKENS,
+    TRAIT_TOKENS,
+    TRAIT_COUNTS,
+    RARITY,
+    RARITY_RANK,
+    RARITY_STATE,
+    RARITY_RANKS,
+    SvgConf,
+    SVG_CONF,
+    STORED_TOKENS,
+    STORED_COUNT,
+    MINTED_TOKENS,
+    MINTED_COUNT,
+    SVG_LAYERS,
+    PAUSE_WINDOWS,
+    PAUSE_EXPIRES,
     BURNT_AMOUNT,
+    PLEDGED_TOKENS,
+    STATS,
+    HOLDER_BUCKETS,
+    HOLDER_BALANCES,
+    TOKEN_OWNERS,
     // BURNT_LIST,
     // BURNED
 };
@@ -28,30 +104,151 @@ pub fn query_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
 }
 
+// Page of the requested ids after start_after, none when start_after is not requested
+fn query_batch_page(
+    deps: Deps,
+    tokens: Vec<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let max = CONFIG.load(deps.storage)?.limits.max_query_batch;
+
+    if tokens.len() > max as usize {
+        return Err(to_std_error(ContractError::RequestTooLarge { size: tokens.len(), max }))
+    }
+
+    let start = match start_after {
+        Some(start_after) => match tokens.iter().position(|token_id| *token_id == start_after) {
+            Some(index) => index + 1,
+            None => return Err(StdError::generic_err(format!("start_after {} is not a requested token", start_after))),
+        },
+        None => 0,
+    };
+
+    // the request is already bounded by max_query_batch, no limit returns all of it
+    let limit = limit.map_or(tokens.len(), |limit| limit as usize);
+
+    Ok(tokens.into_iter().skip(start).take(limit).collect())
+}
+
+// Burnt tokens stay flagged in the pledged tokens
+fn token_status(
+    deps: Deps,
+    contract: &CW721Contract,
+    token_id: &str,
+) -> StdResult<TokenStatus> {
+    if contract.tokens.has(deps.storage, token_id) {
+        return Ok(TokenStatus::Found)
+    }
+
+    match PLEDGED_TOKENS.may_load(deps.storage, token_id.to_string())? {
+        Some(true) => Ok(TokenStatus::Burnt),
+        _ => Ok(TokenStatus::NotFound),
+    }
+}
+
 pub fn query_nft_info_batch(
     deps: Deps,
-    tokens: Vec<String>
-) -> StdResult<Vec<TokenInfo<Extension>>> {
-    if tokens.len() > 30 {
-        return Err(StdError::generic_err("request too large"))
+    tokens: Vec<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<NftInfoBatchResponse> {
+    let tokens = query_batch_page(deps, tokens, start_after, limit)?;
+
+    let contract = CW721Contract::default();
+    let mut data: Vec<NftInfoBatchItem> = vec![];
+
+    for token_id in tokens {
+        let status = token_status(deps, &contract, &token_id)?;
+
+        let info = match status {
+            TokenStatus::Found => {
+                let token = render_token(deps.storage, &contract, &token_id).map_err(to_std_error)?;
+
+                Some(BatchTokenInfo {
+                    owner: token.owner,
+                    approvals: token.approvals.into_iter()
+                        .map(|approval| Approval { spender: approval.spender.to_string(), expires: approval.expires })
+                        .collect(),
+                    token_uri: token.token_uri,
+                    extension: token.extension,
+                })
+            },
+            _ => None,
+        };
+
+        data.push(NftInfoBatchItem { token_id, status, info });
     }
 
+    Ok(NftInfoBatchResponse { tokens: data })
+}
+
+pub fn query_all_nft_info_batch(
+    deps: Deps,
+    env: Env,
+    tokens: Vec<String>,
+    include_expired: Option<bool>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllNftInfoBatchResponse> {
+    let tokens = query_batch_page(deps, tokens, start_after, limit)?;
+
     let contract = CW721Contract::default();
-    let mut data: Vec<TokenInfo<Extension>> = vec![];
-
-    for token in tokens {
-        // match contract.tokens.may_load(deps.storage, &token).unwrap() {
-        //     Some(item) => data.push(item),
-        //     None => {}
-        // }
-        if let Some(item) = contract.tokens
-            .may_load(deps.storage, &token)
-            .unwrap() {
-            data.push(item)
-        }
+    let mut data: Vec<AllNftInfoBatchItem> = vec![];
+
+    for token_id in tokens {
+        let status = token_status(deps, &contract, &token_id)?;
+
+        let info = match status {
+            TokenStatus::Found => Some(query_all_nft_info(deps, env.clone(), token_id.clone(), include_expired)?),
+            _ => None,
+        };
+
+        data.push(AllNftInfoBatchItem { token_id, status, info });
+    }
+
+    Ok(AllNftInfoBatchResponse { tokens: data })
+}
+
+// Keep storage errors as they are so missing tokens still report not found
+fn to_std_error(err: ContractError) -> StdError {
+    match err {
+        ContractError::Std(err) => err,
+        err => StdError::generic_err(err.to_string()),
     }
+}
 
-    Ok(data)
+pub fn query_nft_info(
+    deps: Deps,
+    token_id: String,
+) -> StdResult<NftInfoResponse<Extension>> {
+    let token = render_token(deps.storage, &CW721Contract::default(), &token_id).map_err(to_std_error)?;
+
+    Ok(NftInfoResponse {
+        token_uri: token.token_uri,
+        extension: token.extension,
+    })
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: Option<bool>,
+) -> StdResult<AllNftInfoResponse<Extension>> {
+    let mut res = CW721Contract::default().all_nft_info(
+        deps,
+        env,
+        token_id.clone(),
+        include_expired.unwrap_or(false)
+    )?;
+
+    // compact tokens and generated images are rebuilt on read
+    if COMPACT_TOKENS.has(deps.storage, &token_id) || SVG_CONF.exists(deps.storage) {
+        res.info = query_nft_info(deps, token_id)?;
+    }
+
+    Ok(res)
 }
 
 pub fn query_burnt_amount(
@@ -61,6 +258,395 @@ pub fn query_burnt_amount(
     BURNT_AMOUNT.may_load(deps.storage, &address)
 }
 
+pub fn query_roles(
+    deps: Deps,
+    address: String,
+) -> StdResult<RolesResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+
+    let mut roles = ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+
+    // the creator is always an admin
+    if addr == CONFIG.load(deps.storage)?.creator && !roles.contains(&Role::Admin) {
+        roles.insert(0, Role::Admin);
+    }
+
+    Ok(RolesResponse { address, roles })
+}
+
+pub fn query_pause_schedule(
+    deps: Deps,
+    env: Env,
+) -> StdResult<PauseScheduleResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(PauseScheduleResponse {
+        paused: check_paused(deps.storage, &config, &env.block).is_err(),
+        paused_until: PAUSE_EXPIRES.may_load(deps.storage)?,
+        windows: PAUSE_WINDOWS.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_locks(
+    deps: Deps,
+) -> StdResult<Locks> {
+    load_locks(deps.storage).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+pub fn query_trait_changes(
+    deps: Deps,
+    token_id: String,
+) -> StdResult<u64> {
+    Ok(TRAIT_CHANGES.may_load(deps.storage, &token_id)?.unwrap_or_default())
+}
+
+pub fn query_tokens_by_trait(
+    deps: Deps,
+    trait_type: String,
+    value: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = CONFIG.load(deps.storage)?.limits.query_limit(limit);
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let tokens = TRAIT_TOKENS
+        .prefix((trait_type.as_str(), value.as_str()))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<String>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
+
+// Page through the stored or minted token list
+fn list_tokens(
+    deps: Deps,
+    list: &Map<&str, bool>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = CONFIG.load(deps.storage)?.limits.query_limit(limit);
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let tokens = list
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<String>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_stored_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    list_tokens(deps, &STORED_TOKENS, start_after, limit)
+}
+
+pub fn query_minted_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    list_tokens(deps, &MINTED_TOKENS, start_after, limit)
+}
+
+pub fn query_token_counts(
+    deps: Deps,
+) -> StdResult<TokenCountsResponse> {
+    Ok(TokenCountsResponse {
+        stored: STORED_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+        minted: MINTED_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_trait_counts(
+    deps: Deps,
+    trait_type: String,
+) -> StdResult<TraitCountsResponse> {
+    let counts = TRAIT_COUNTS
+        .prefix(trait_type.as_str())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(value, count)| TraitCount { value, count }))
+        .collect::<StdResult<Vec<TraitCount>>>()?;
+
+    Ok(TraitCountsResponse { trait_type, counts })
+}
+
+pub fn query_mint_status(
+    deps: Deps,
+    env: Env,
+) -> StdResult<MintStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    // the cw721 token count is the mint cursor, it also counts unstored and burnt tokens
+    let current_count = CW721Contract::default().token_count(deps.storage)?;
+
+    let reason = check_mint_open(deps.storage, &env.block, &current_count, &config)
+        .err()
+        .map(|err| match err {
+            ContractError::ContractFrozen {} => MintBlockedReason::ContractFrozen,
+            ContractError::ContractPaused {} => MintBlockedReason::ContractPaused,
+            ContractError::ContractPausedScheduled {} => MintBlockedReason::ContractPaused,
+            ContractError::CantMintNothing {} => MintBlockedReason::NothingStored,
+            ContractError::CantMintYet {} => MintBlockedReason::NotStarted,
+            ContractError::MintEnded {} => MintBlockedReason::Ended,
+            ContractError::MaxTokenSupply {} => MintBlockedReason::MaxTokenSupply,
+            ContractError::MaxTokens {} => MintBlockedReason::SoldOut,
+            ContractError::SnapshotsNotCleared {} => MintBlockedReason::SnapshotsClearing,
+            _ => MintBlockedReason::Other,
+        });
+
+    let paused = check_paused(deps.storage, &config, &env.block).is_err();
+
+    Ok(MintStatusResponse {
+        open: reason.is_none(),
+        reason,
+        phase: config.dates.phase(&env.block.time),
+        start: config.dates.start,
+        end: config.dates.end,
+        token_total: config.token_total,
+        token_supply: config.token_supply,
+        minted: MINTED_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+        remaining: Uint128::from(STORED_COUNT.may_load(deps.storage)?.unwrap_or_default()),
+        price: config.cost,
+        max_mint_batch: config.max_mint_batch,
+        paused,
+        paused_until: PAUSE_EXPIRES.may_load(deps.storage)?,
+        frozen: config.frozen,
+    })
+}
+
+// Same checks as a mint batch, returns the token ids and cost
+fn simulate_mint(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    amount: Uint128,
+) -> Result<(Vec<String>, Coin), ContractError> {
+    let contract = CW721Contract::default();
+    let config = CONFIG.load(deps.storage)?;
+
+    let minted_total = contract.token_count(deps.storage)?;
+    let minter = contract.minter.load(deps.storage)?;
+
+    let current = can_mint(deps.storage, &env.block, &minted_total, &config, &amount, &minter, &info.sender)?;
+    let cost = can_pay(&config, info, &amount)?;
+
+    let mut tokens: Vec<String> = vec![];
+
+    for index in 0..amount.u128() {
+        let token_id = config.token_id_scheme.token_id(current + Uint128::from(index));
+
+        check_mintable(deps.storage, &minter, &contract, &token_id)?;
+
+        tokens.push(token_id);
+    }
+
+    Ok((tokens, cost))
+}
+
+pub fn query_simulate_mint(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    amount: Uint128,
+    funds: Vec<Coin>,
+) -> StdResult<SimulateMintResponse> {
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&sender)?,
+        funds,
+    };
+
+    Ok(match simulate_mint(deps, &env, &info, amount) {
+        Ok((tokens, cost)) => SimulateMintResponse { tokens, cost: Some(cost), error: None },
+        Err(err) => SimulateMintResponse { tokens: vec![], cost: None, error: Some(err.to_string()) },
+    })
+}
+
+// Same checks as a burn batch
+fn simulate_burn(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    tokens: Vec<String>,
+) -> Result<Vec<String>, ContractError> {
+    check_pause_windows(deps.storage, &env.block)?;
+
+    let contract = CW721Contract::default();
+    let config = CONFIG.load(deps.storage)?;
+    let is_burner = has_role(deps.storage, sender, &Role::Burner)?;
+
+    check_batch_size(tokens.len(), config.limits.max_token_batch)?;
+
+    if tokens.is_empty() {
+        return Err(ContractError::RequestTooSmall { size: tokens.len() })
+    }
+
+    if !config.burn.owner_can_burn && !config.burn.can_burn_owned {
+        return Err(ContractError::BurnDisabled {})
+    }
+
+    let minter = contract.minter.load(deps.storage)?;
+
+    for token_id in tokens.iter() {
+        let token = check_burnable(&contract, deps.storage, sender, is_burner, token_id)?;
+
+        // the minter's tokens are not holdings, burning them moves no snapshot
+        if token.owner != minter {
+            check_snapshots_cleared(deps.storage)?;
+        }
+    }
+
+    Ok(tokens)
+}
+
+pub fn query_simulate_burn(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    tokens: Vec<String>,
+) -> StdResult<SimulateBurnResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+
+    Ok(match simulate_burn(deps, &env, &sender, tokens) {
+        Ok(tokens) => SimulateBurnResponse { tokens, error: None },
+        Err(err) => SimulateBurnResponse { tokens: vec![], error: Some(err.to_string()) },
+    })
+}
+
+pub fn query_svg_conf(
+    deps: Deps,
+) -> StdResult<Option<SvgConf>> {
+    SVG_CONF.may_load(deps.storage)
+}
+
+pub fn query_svg_layer(
+    deps: Deps,
+    trait_type: String,
+    value: String,
+) -> StdResult<Option<String>> {
+    SVG_LAYERS.may_load(deps.storage, (trait_type.as_str(), value.as_str()))
+}
+
+// Scores are stale until a computation runs to the end
+fn rarity_stale(deps: Deps) -> StdResult<bool> {
+    Ok(!RARITY_STATE.may_load(deps.storage)?.unwrap_or_default().done)
+}
+
+pub fn query_rarity(
+    deps: Deps,
+    token_id: String,
+) -> StdResult<RarityResponse> {
+    let score = RARITY.load(deps.storage, &token_id)?;
+    let rank = RARITY_RANKS.may_load(deps.storage, &token_id)?;
+
+    Ok(RarityResponse {
+        stale: rarity_stale(deps)?,
+        token_id,
+        score,
+        rank,
+    })
+}
+
+pub fn query_rarity_rank(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RarityRankResponse> {
+    let limit = CONFIG.load(deps.storage)?.limits.query_limit(limit);
+
+    let end = match start_after.as_deref() {
+        Some(token_id) => Some(Bound::exclusive((RARITY.load(deps.storage, token_id)?.u128(), token_id))),
+        None => None,
+    };
+
+    let tokens = RARITY_RANK
+        .keys(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(score, token_id)| TokenRarity { token_id, score: Uint128::from(score) }))
+        .collect::<StdResult<Vec<TokenRarity>>>()?;
+
+    Ok(RarityRankResponse {
+        tokens,
+        stale: rarity_stale(deps)?,
+    })
+}
+
+pub fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    stats.buckets.resize(HOLDER_BUCKETS.len(), 0);
+
+    let buckets = HOLDER_BUCKETS
+        .iter()
+        .enumerate()
+        .map(|(index, min)| HolderBucket {
+            min: *min,
+            max: HOLDER_BUCKETS.get(index + 1).map(|next| next - 1),
+            holders: stats.buckets[index],
+        })
+        .collect();
+
+    Ok(StatsResponse {
+        holders: stats.holders,
+        buckets,
+        burnt: stats.burnt,
+        pledged: stats.pledged,
+        revenue: stats.revenue,
+        mints: stats.mints,
+    })
+}
+
+pub fn query_balance(
+    deps: Deps,
+    owner: String,
+) -> StdResult<BalanceResponse> {
+    let addr = deps.api.addr_validate(&owner)?;
+    let balance = HOLDER_BALANCES.may_load(deps.storage, &addr)?.unwrap_or_default();
+
+    Ok(BalanceResponse { owner, balance })
+}
+
+pub fn query_holders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<HoldersResponse> {
+    let limit = CONFIG.load(deps.storage)?.limits.query_limit(limit);
+    let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let holders = HOLDER_BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(address, balance)| Holder { address, balance }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(HoldersResponse { holders })
+}
+
+pub fn query_owner_of_at_height(
+    deps: Deps,
+    token_id: String,
+    height: u64,
+) -> StdResult<OwnerOfAtHeightResponse> {
+    let owner = TOKEN_OWNERS.may_load_at_height(deps.storage, &token_id, height)?;
+
+    Ok(OwnerOfAtHeightResponse { token_id, height, owner })
+}
+
+pub fn query_balance_at_height(
+    deps: Deps,
+    owner: String,
+    height: u64,
+) -> StdResult<BalanceAtHeightResponse> {
+    let addr = deps.api.addr_validate(&owner)?;
+    let balance = HOLDER_BALANCES.may_load_at_height(deps.storage, &addr, height)?.unwrap_or_default();
+
+    Ok(BalanceAtHeightResponse { owner, height, balance })
+}
+
 // pub fn query_burnt_list(
 //     deps: Deps,
 //     address: Addr,
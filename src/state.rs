@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
 // use crate::error::ContractError;
-use cw_storage_plus::{Item, Map};
-use cosmwasm_std::{Addr, Empty, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use cosmwasm_std::{Addr, Coin, Empty, Uint128};
 
 pub type Extension = Option<Metadata>;
 pub type CW721Contract<'a> = cw721_base::Cw721Contract<'a, Extension, Empty, Empty, Empty>;
@@ -12,7 +12,81 @@ pub const BURNT_AMOUNT: Map<&Addr, Uint128> = Map::new("burnt");
 pub const PLEDGED_TOKENS_BY_ADDR: Map<&Addr, Vec<String>> = Map::new("pba");
 pub const PLEDGED_TOKENS: Map<String, bool> = Map::new("pledged");
 
-// use cw_utils::{Expiration, Scheduled};
+// scheduled pause windows
+pub const PAUSE_WINDOWS: Item<Vec<types_mint::PauseWindow>> = Item::new("pause_windows");
+// when set a manual pause is lifted once it is triggered
+pub const PAUSE_EXPIRES: Item<Scheduled> = Item::new("pause_expires");
+
+// one way locks, once set they can not be lifted
+pub const LOCKS: Item<Locks> = Item::new("locks");
+
+// per token metadata locks
+pub const TOKEN_METADATA_LOCKS: Map<&str, bool> = Map::new("token_locks");
+
+// amount of trait changes per token
+pub const TRAIT_CHANGES: Map<&str, u64> = Map::new("trait_changes");
+
+// roles granted to an address, the config creator is always an admin
+pub const ROLES: Map<&Addr, Vec<Role>> = Map::new("roles");
+
+// store confs shared by compact tokens, keyed by version
+pub const STORE_CONFS: Map<u64, StoreConf> = Map::new("store_confs");
+pub const STORE_CONF_VERSION: Item<u64> = Item::new("store_conf_version");
+
+// trait index, (trait_type, value, token_id)
+pub const TRAIT_TOKENS: Map<(&str, &str, &str), bool> = Map::new("trait_tokens");
+// amount of tokens per (trait_type, value)
+pub const TRAIT_COUNTS: Map<(&str, &str), u64> = Map::new("trait_counts");
+// tokens with at least one indexed trait, the total rarity scores scale by
+pub const TRAITED_TOKENS: Map<&str, bool> = Map::new("traited_tokens");
+pub const TRAITED_COUNT: Item<u64> = Item::new("traited_count");
+
+// rarity score per token
+pub const RARITY: Map<&str, Uint128> = Map::new("rarity");
+// rarity ranking, (score, token_id)
+pub const RARITY_RANK: Map<(u128, &str), bool> = Map::new("rarity_rank");
+// rank per token, written by the ranking step of a computation
+pub const RARITY_RANKS: Map<&str, u64> = Map::new("rarity_ranks");
+pub const RARITY_STATE: Item<RarityState> = Item::new("rarity_state");
+
+// unminted tokens still owned by the minter
+pub const STORED_TOKENS: Map<&str, bool> = Map::new("stored_tokens");
+pub const STORED_COUNT: Item<u64> = Item::new("stored_count");
+// tokens claimed by a mint
+pub const MINTED_TOKENS: Map<&str, bool> = Map::new("minted_tokens");
+pub const MINTED_COUNT: Item<u64> = Item::new("minted_count");
+
+// svg fragments per (trait_type, value)
+pub const SVG_LAYERS: Map<(&str, &str), String> = Map::new("svg_layers");
+// when set token images are generated from the layers
+pub const SVG_CONF: Item<SvgConf> = Item::new("svg_conf");
+
+// compact tokens only keep their trait indexes, metadata is rebuilt on read
+pub const COMPACT_TOKENS: Map<&str, CompactToken> = Map::new("compact_tokens");
+
+// amount of tokens per owner, the minter and its stored tokens are left out
+pub const HOLDER_BALANCES: SnapshotMap<&Addr, u64> = SnapshotMap::new(
+    "holder_balances",
+    "holder_balances__checkpoints",
+    "holder_balances__changelog",
+    Strategy::EveryBlock,
+);
+// owner of every minted token, snapshotted for lookups at a block height
+pub const TOKEN_OWNERS: SnapshotMap<&str, Addr> = SnapshotMap::new(
+    "token_owners",
+    "token_owners__checkpoints",
+    "token_owners__changelog",
+    Strategy::EveryBlock,
+);
+// set by a clear state migration until every snapshot entry is removed
+pub const SNAPSHOT_CLEAR: Item<bool> = Item::new("snapshot_clear");
+// collection stats kept up to date by every mint, transfer, burn and pledge
+pub const STATS: Item<Stats> = Item::new("stats");
+
+// lower bound of every holder balance bucket
+pub const HOLDER_BUCKETS: [u64; 6] = [1, 2, 5, 10, 25, 50];
+
+use cw_utils::Scheduled;
 use crate::{
     msg::StoreConf,
     types_mint
@@ -32,6 +106,199 @@ pub struct Config {
     pub store_conf: StoreConf,
     pub frozen: bool,
     pub paused: bool,
+    // configs saved before the scheme existed use plain indexes
+    #[serde(default)]
+    pub token_id_scheme: types_mint::TokenIdScheme,
+    // metadata rules, none accepts any metadata
+    #[serde(default)]
+    pub schema: Option<types_mint::CollectionSchema>,
+    // configs saved before limits existed use the defaults
+    #[serde(default)]
+    pub limits: types_mint::Limits,
+}
+
+// Named permissions checked per execute message
+// admins implicitly hold every other role
+#[cw_serde]
+pub enum Role {
+    Admin,
+    MetadataManager,
+    Pauser,
+    Burner,
+    Treasurer,
+    // game contracts allowed to update token traits
+    TraitUpdater,
+}
+
+// Areas that can be locked for good
+#[cw_serde]
+pub enum LockArea {
+    // token metadata, collection name and store conf
+    Metadata,
+    // token supply
+    Supply,
+    // mint cost
+    Price,
+    // funds wallet
+    Payout,
+    // svg layers and their order
+    Layers,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Locks {
+    pub metadata: bool,
+    pub supply: bool,
+    pub price: bool,
+    pub payout: bool,
+    // locks saved before layers existed
+    #[serde(default)]
+    pub layers: bool,
+}
+
+impl Locks {
+    pub fn all() -> Self {
+        Self {
+            metadata: true,
+            supply: true,
+            price: true,
+            payout: true,
+            layers: true,
+        }
+    }
+
+    pub fn is_locked(&self, area: &LockArea) -> bool {
+        match area {
+            LockArea::Metadata => self.metadata,
+            LockArea::Supply => self.supply,
+            LockArea::Price => self.price,
+            LockArea::Payout => self.payout,
+            LockArea::Layers => self.layers,
+        }
+    }
+
+    pub fn lock(&mut self, area: &LockArea) {
+        match area {
+            LockArea::Metadata => self.metadata = true,
+            LockArea::Supply => self.supply = true,
+            LockArea::Price => self.price = true,
+            LockArea::Payout => self.payout = true,
+            LockArea::Layers => self.layers = true,
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RarityState {
+    // last token scored by the running computation
+    pub cursor: Option<String>,
+    // every token was scored and ranked with the current trait counts
+    pub done: bool,
+    // burns restart the computation
+    pub recompute_on_burn: bool,
+    // every token was scored, ranks are being written
+    #[serde(default)]
+    pub scored: bool,
+    // last (score, token) ranked by the running computation
+    #[serde(default)]
+    pub rank_cursor: Option<(Uint128, String)>,
+    #[serde(default)]
+    pub ranked: u64,
+}
+
+impl RarityState {
+    // Start scoring from the first token again
+    pub fn restart(&mut self) {
+        self.cursor = None;
+        self.done = false;
+        self.scored = false;
+        self.rank_cursor = None;
+        self.ranked = 0;
+    }
+}
+
+#[cw_serde]
+pub struct SvgConf {
+    // trait types composited from bottom to top
+    pub layer_order: Vec<String>,
+    // e.g. "0 0 32 32"
+    pub view_box: String,
+}
+
+#[cw_serde]
+pub struct SvgLayer {
+    pub trait_type: String,
+    pub value: String,
+    pub svg: String,
+}
+
+#[cw_serde]
+pub struct CompactToken {
+    // version of the store conf in STORE_CONFS
+    pub conf: u64,
+    // position of the token in the collection
+    pub index: Uint128,
+    // indexes into the store conf values
+    pub row: Vec<u32>,
+}
+
+// mints of a sale phase, every dates update opens a new phase
+#[cw_serde]
+pub struct PhaseMints {
+    pub phase: types_mint::MintPhase,
+    pub dates: types_mint::Dates,
+    pub count: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Stats {
+    pub holders: u64,
+    // holders per balance bucket, parallel to HOLDER_BUCKETS
+    pub buckets: Vec<u64>,
+    pub burnt: u64,
+    // pledged tokens not burnt yet
+    pub pledged: u64,
+    pub revenue: Vec<Coin>,
+    #[serde(default)]
+    pub mints: Vec<PhaseMints>,
+}
+
+impl Stats {
+    fn bucket(balance: u64) -> usize {
+        HOLDER_BUCKETS.iter().rposition(|min| balance >= *min).unwrap_or(0)
+    }
+
+    // Move a holder between buckets when its balance changes
+    pub fn update_holder(&mut self, old: u64, new: u64) {
+        self.buckets.resize(HOLDER_BUCKETS.len(), 0);
+
+        if old > 0 {
+            self.holders = self.holders.saturating_sub(1);
+            self.buckets[Self::bucket(old)] = self.buckets[Self::bucket(old)].saturating_sub(1);
+        }
+
+        if new > 0 {
+            self.holders += 1;
+            self.buckets[Self::bucket(new)] += 1;
+        }
+    }
+
+    pub fn add_revenue(&mut self, coin: &Coin) {
+        match self.revenue.iter_mut().find(|item| item.denom == coin.denom) {
+            Some(item) => item.amount += coin.amount,
+            None => self.revenue.push(coin.clone()),
+        }
+    }
+
+    pub fn add_mints(&mut self, phase: types_mint::MintPhase, dates: &types_mint::Dates, count: u64) {
+        match self.mints.iter_mut().find(|item| item.phase == phase && item.dates == *dates) {
+            Some(item) => item.count += count,
+            None => self.mints.push(PhaseMints { phase, dates: dates.clone(), count }),
+        }
+    }
 }
 
 #[cw_serde]
@@ -43,6 +310,7 @@ pub struct Trait {
 
 // see: https://docs.opensea.io/docs/metadata-standards
 #[cw_serde]
+#[derive(Default)]
 pub struct Metadata {
     pub image: Option<String>,
     pub image_data: Option<String>,
@@ -14,6 +14,8 @@ mod general {
         mock_info,
     };
 
+    use crate::error::ContractError;
+    use crate::msg::SimulateBurnResponse;
     use crate::{
         contract::{
             execute,
@@ -45,7 +47,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -205,7 +207,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         init_msg.burn.owner_can_burn = false;
         init_msg.burn.can_burn_owned = true;
@@ -283,7 +285,7 @@ mod general {
         let mut deps = mock_dependencies();
         let info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         init_msg.burn.owner_can_burn = false;
         init_msg.burn.can_burn_owned = false;
@@ -529,7 +531,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -591,7 +593,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = false;
@@ -723,7 +725,8 @@ mod general {
 
         let mut tokens = vec![];
 
-        for item in 0..30 {
+        // one above the default max of 30
+        for item in 0..31 {
             tokens.push(String::from(item.to_string()))
         }
 
@@ -816,7 +819,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = false;
@@ -883,8 +886,18 @@ mod general {
         assert_eq!(res.attributes[1].key, "list");
         assert_eq!(res.attributes[1].value, "[\"0\", \"1\", \"2\"]");
 
+        let tokens = vec![String::from("0"), String::from("1"), String::from("2")];
+
+        let res: SimulateBurnResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateBurn { sender: MINTER.to_string(), tokens }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.error, Some(ContractError::BurnDisabled {}.to_string()));
+
         // BURN BABY BURN
-        let res = execute(
+        let err = execute(
             deps.as_mut(),
             mock_env(),
             info,
@@ -895,9 +908,9 @@ mod general {
                     String::from("2")
                 ]
             }
-        ).unwrap();
+        ).unwrap_err();
 
-        assert_eq!(res.attributes[0].value, "burn_nothing");
+        assert_eq!(err, ContractError::BurnDisabled {});
     }
 }
 
@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod general {
     use cosmwasm_std::{
+        from_binary,
+        Addr,
         Uint128,
         Response,
+        Timestamp,
     };
 
     use cosmwasm_std::testing::{
@@ -11,21 +14,67 @@ mod general {
         mock_info,
     };
 
+    use crate::error::ContractError;
+    use crate::msg::StoreConf;
+    use crate::state::{Config, LockArea, Locks, Role, CONFIG};
     use crate::types_mint;
     use crate::{
         contract::{
             execute,
-            instantiate
+            instantiate,
+            query,
         },
         msg::{
-            ExecuteMsg
+            ExecuteMsg,
+            QueryMsg,
+            UpdateConfigMsg,
         },
         tests::test_helpers::tests_helpers::{
             get_init_msg,
+            get_mint_msg,
+            get_store_batch_msg,
         }
     };
 
     const ADMIN: &str = "admin";
+    const TREASURER: &str = "treasurer";
+
+    #[test]
+    fn instantiate_validation() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let mut msg = get_init_msg(0, 900);
+        msg.token_supply = Uint128::zero();
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::SupplyZero {});
+
+        let msg = get_init_msg(900, 10);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidDates {});
+
+        let msg = get_init_msg(900, 900);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidDates {});
+
+        let mut msg = get_init_msg(0, 900);
+        msg.wallet = types_mint::Wallet::default();
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidWallet { address: String::from("") });
+
+        let mut msg = get_init_msg(0, 900);
+        msg.cost.amount = Uint128::zero();
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::CostZero {});
+
+        let mut msg = get_init_msg(0, 900);
+        msg.max_mint_batch = Some(Uint128::from(100u32));
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::MaxMintBatchTooLarge {
+            max_mint_batch: Uint128::from(100u32),
+            supply: Uint128::from(50u32),
+        });
+    }
 
     #[test]
     fn update_conf() {
@@ -34,28 +83,182 @@ mod general {
 
         instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
 
-        // store batch
         let res: Response = execute(
             deps.as_mut(),
             mock_env(),
             info,
-            ExecuteMsg::UpdateConf(crate::msg::InstantiateMsg {
-                creator: String::from(ADMIN),
-                name: String::from("nft2"),
-                symbol: String::from("NFT2"),
-                dates: types_mint::Dates::default(),
-                cost: types_mint::Costs::default(),
-                burn: types_mint::Burn::default(),
-                token_supply: Default::default(),
-                wallet: types_mint::Wallet::default(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                name: Some(String::from("nft2")),
                 max_mint_batch: Some(Uint128::from(8u32)),
-                store_conf: Default::default(),
+                cost: Some(types_mint::Costs::default()),
+                ..Default::default()
             })
         ).unwrap();
 
        assert_eq!(res.attributes[0].value, "config");
        assert_eq!(res.attributes[1].value, "update");
        assert_eq!(res.attributes[2].value, "success");
+       assert_eq!(res.attributes[3].key, "changed");
+       assert_eq!(res.attributes[3].value, "name,cost,max_mint_batch");
+    }
+
+    #[test]
+    fn update_conf_keeps_total() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StoreBatch(get_store_batch_msg(20))
+        ).unwrap();
+
+        // supply can not drop below the stored tokens
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                token_supply: Some(Uint128::from(10u32)),
+                ..Default::default()
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::SupplyBelowTotal {
+            supply: Uint128::from(10u32),
+            total: Uint128::from(20u32),
+        });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                token_supply: Some(Uint128::from(30u32)),
+                ..Default::default()
+            })
+        ).unwrap();
+
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+        assert_eq!(config.token_supply, Uint128::from(30u32));
+        assert_eq!(config.token_total, Uint128::from(20u32));
+    }
+
+    #[test]
+    fn update_conf_invalid_dates() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                dates: Some(types_mint::Dates {
+                    start: Some(Timestamp::from_seconds(100)),
+                    end: Some(Timestamp::from_seconds(10)),
+                }),
+                ..Default::default()
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidDates {});
+    }
+
+    #[test]
+    fn update_conf_legacy_store_conf() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        // confs saved before the values dictionary existed have no values
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.store_conf = StoreConf {
+            attributes: vec![String::from("background")],
+            ..Default::default()
+        };
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                token_supply: Some(Uint128::from(100u32)),
+                ..Default::default()
+            })
+        ).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                store_conf: Some(StoreConf {
+                    attributes: vec![String::from("background"), String::from("eyes")],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidAttributeDictionary { attributes: 2, values: 0 });
+    }
+
+    #[test]
+    fn treasurer_updates_payout() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::GrantRole { address: String::from(TREASURER), role: Role::Treasurer }
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(TREASURER, &[]),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                wallet: Some(types_mint::Wallet {
+                    name: String::from("treasury"),
+                    wallet: Addr::unchecked(TREASURER),
+                }),
+                ..Default::default()
+            })
+        ).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(TREASURER, &[]),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                name: Some(String::from("nft2")),
+                ..Default::default()
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::MissingRole { role: Role::Admin });
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg::default())
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::EmptyConfigUpdate {});
     }
 
     #[test]
@@ -73,22 +276,14 @@ mod general {
 
         assert_eq!(res.attributes[0].value, "freeze");
 
-        // store batch
         let res: Response = execute(
             deps.as_mut(),
             mock_env(),
             info,
-            ExecuteMsg::UpdateConf(crate::msg::InstantiateMsg {
-                creator: String::from(ADMIN),
-                name: String::from("nft2"),
-                symbol: String::from("NFT2"),
-                dates: types_mint::Dates::default(),
-                cost: types_mint::Costs::default(),
-                burn: types_mint::Burn::default(),
-                token_supply: Default::default(),
-                wallet: types_mint::Wallet::default(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                name: Some(String::from("nft2")),
                 max_mint_batch: Some(Uint128::from(8u32)),
-                store_conf: Default::default(),
+                ..Default::default()
             })
         ).unwrap();
 
@@ -104,7 +299,7 @@ mod general {
 
         instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
 
-        let msg = ExecuteMsg::Pause();
+        let msg = ExecuteMsg::Pause { until: None };
 
         let res: Response = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -140,17 +335,73 @@ mod general {
     }
 
     #[test]
-    fn unfreeze() {
+    fn lock() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let res: Response = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Lock { areas: vec![LockArea::Payout] }
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "lock");
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                wallet: Some(types_mint::Wallet {
+                    name: String::from("treasury"),
+                    wallet: Addr::unchecked(TREASURER),
+                }),
+                ..Default::default()
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::Locked { area: LockArea::Payout });
+
+        // other areas can still be updated
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                token_supply: Some(Uint128::from(100u32)),
+                ..Default::default()
+            })
+        ).unwrap();
+
+        let locks: Locks = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Locks {}).unwrap()).unwrap();
+
+        assert_eq!(locks, Locks { payout: true, ..Default::default() });
+    }
+
+    #[test]
+    fn freeze_locks_everything() {
         let mut deps = mock_dependencies();
         let info = mock_info(ADMIN, &[]);
 
         instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
 
-        let msg = ExecuteMsg::Unfreeze();
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Freeze()).unwrap();
 
-        let res: Response = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let locks: Locks = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Locks {}).unwrap()).unwrap();
+
+        assert_eq!(locks, Locks::all());
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Store(get_mint_msg(String::from("0")))
+        ).unwrap_err();
 
-        assert_eq!(res.attributes[0].value, "unfreeze");
+        assert_eq!(err, ContractError::ContractFrozen {});
     }
 }
 
@@ -0,0 +1,304 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::from_binary;
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use cw721::{NftInfoResponse, TokensResponse};
+
+    use crate::error::ContractError;
+    use crate::msg::{TraitCount, TraitCountsResponse, UpdateMetadataMsg};
+    use crate::state::{Extension, LockArea, Metadata, Role, Trait};
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        tests::test_helpers::tests_helpers::{
+            get_init_msg,
+            get_store_batch_msg,
+        }
+    };
+
+    const ADMIN: &str = "admin";
+    const GAME: &str = "game";
+
+    fn get_metadata(image: &str) -> Extension {
+        Some(Metadata {
+            image: Some(image.to_string()),
+            image_data: None,
+            external_url: None,
+            description: None,
+            name: Some(String::from("nft")),
+            attributes: None,
+            background_color: None,
+            animation_url: None,
+            youtube_url: None,
+        })
+    }
+
+    #[test]
+    fn update_metadata() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(2))).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateMetadataBatch {
+                tokens: vec![
+                    UpdateMetadataMsg {
+                        token_id: String::from("0"),
+                        token_uri: None,
+                        extension: get_metadata("ipfs://cid/0.png"),
+                    },
+                    UpdateMetadataMsg {
+                        token_id: String::from("1"),
+                        token_uri: None,
+                        extension: get_metadata("ipfs://cid/1.png"),
+                    },
+                ]
+            }
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "update_metadata");
+        assert_eq!(res.attributes[1].value, "2");
+        assert_eq!(res.events.len(), 2);
+        assert_eq!(res.events[0].ty, "update_metadata");
+        assert_eq!(res.events[0].attributes[0].value, "0");
+        assert_ne!(res.events[0].attributes[1].value, res.events[0].attributes[2].value);
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("1") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.extension, get_metadata("ipfs://cid/1.png"));
+    }
+
+    #[test]
+    fn update_locked_metadata() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(2))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::LockMetadata { tokens: vec![String::from("0")] }
+        ).unwrap();
+
+        let update = UpdateMetadataMsg {
+            token_id: String::from("0"),
+            token_uri: None,
+            extension: get_metadata("ipfs://cid/0.png"),
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateMetadata(update.clone())
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::TokenMetadataLocked { token_id: String::from("0") });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Lock { areas: vec![LockArea::Metadata] }
+        ).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateMetadata(UpdateMetadataMsg { token_id: String::from("1"), ..update })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::Locked { area: LockArea::Metadata });
+    }
+
+    #[test]
+    fn update_traits() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(2))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::GrantRole { address: String::from(GAME), role: Role::TraitUpdater }
+        ).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(GAME, &[]),
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("0"),
+                set: vec![
+                    Trait { display_type: None, trait_type: String::from("level"), value: String::from("1") },
+                    Trait { display_type: None, trait_type: String::from("class"), value: String::from("mage") },
+                ],
+                remove: vec![],
+            }
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "update_traits");
+        assert_eq!(res.events.len(), 2);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(GAME, &[]),
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("0"),
+                set: vec![
+                    Trait { display_type: None, trait_type: String::from("level"), value: String::from("2") },
+                ],
+                remove: vec![String::from("class")],
+            }
+        ).unwrap();
+
+        assert_eq!(res.events[0].attributes[2].value, "1");
+        assert_eq!(res.events[0].attributes[3].value, "2");
+        assert_eq!(res.events[1].attributes[3].value, "");
+
+        let changes: u64 = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TraitChanges { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(changes, 4);
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.extension.unwrap().attributes, Some(vec![
+            Trait { display_type: None, trait_type: String::from("level"), value: String::from("2") },
+        ]));
+    }
+
+    #[test]
+    fn trait_index() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(3))).unwrap();
+
+        let with_background = |value: &str| {
+            let mut metadata = get_metadata("ipfs://image");
+
+            if let Some(metadata) = metadata.as_mut() {
+                metadata.attributes = Some(vec![
+                    Trait { display_type: None, trait_type: String::from("background"), value: value.to_string() },
+                ]);
+            }
+
+            metadata
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateMetadataBatch {
+                tokens: vec![
+                    UpdateMetadataMsg { token_id: String::from("0"), token_uri: None, extension: with_background("gold") },
+                    UpdateMetadataMsg { token_id: String::from("1"), token_uri: None, extension: with_background("gold") },
+                    UpdateMetadataMsg { token_id: String::from("2"), token_uri: None, extension: with_background("silver") },
+                ],
+            }
+        ).unwrap();
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TokensByTrait {
+                trait_type: String::from("background"),
+                value: String::from("gold"),
+                start_after: None,
+                limit: None,
+            }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("0"), String::from("1")]);
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TokensByTrait {
+                trait_type: String::from("background"),
+                value: String::from("gold"),
+                start_after: Some(String::from("0")),
+                limit: Some(1),
+            }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("1")]);
+
+        // trait updates move the token to the new value
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("1"),
+                set: vec![
+                    Trait { display_type: None, trait_type: String::from("background"), value: String::from("silver") },
+                ],
+                remove: vec![],
+            }
+        ).unwrap();
+
+        // removed tokens leave the index
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Unstore { tokens: vec![String::from("2")] }
+        ).unwrap();
+
+        let res: TraitCountsResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TraitCounts { trait_type: String::from("background") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.counts, vec![
+            TraitCount { value: String::from("gold"), count: 1 },
+            TraitCount { value: String::from("silver"), count: 1 },
+        ]);
+    }
+}
@@ -1,41 +1,63 @@
 #[cfg(test)]
 mod general {
     use cosmwasm_std::{
+        from_binary,
+        Addr,
         Uint128,
         Response,
     };
 
+    use cosmwasm_std::OwnedDeps;
     use cosmwasm_std::testing::{
         mock_dependencies,
         mock_env,
         mock_info,
+        MockApi,
+        MockQuerier,
+        MockStorage,
     };
 
+    use cw721::{NftInfoResponse, TokensResponse};
+
     // use cw721_base::MintMsg;
 
-    use crate::msg::MigrateMsg;
+    use crate::error::ContractError;
+    use crate::msg::{MigrateMsg, StoreConf, StoreConfMsg, TokenCountsResponse, TraitCountsResponse, UpdateMetadataMsg};
     // use crate::msg::{StoreConfMsg, StoreConf, MigrateMsg, InstantiateMsg};
-    use crate::state::Config;
+    use crate::state::{Config, Extension, Locks, Trait};
     use crate::{
         types_mint,
         contract::{
-            // execute,
+            execute,
             instantiate,
-            // query,
+            query,
             migrate
         },
-        // msg::{
-        //     ExecuteMsg,
-        //     QueryMsg,
-        // },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
         tests::test_helpers::tests_helpers::{
             // get_store_batch_msg,
             get_init_msg,
+            get_mint_msg,
         }
     };
 
     const ADMIN: &str = "admin";
 
+    // Migrate to a new version keeping the current config
+    fn clear_state_migration(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+        let msg : MigrateMsg<Config> = MigrateMsg::WithConfigClearState {
+          version: String::from("2.0.0"),
+          config
+        };
+
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    }
+
     #[test]
     fn migrate_with_conf_and_clear_state() {
         let mut deps = mock_dependencies();
@@ -45,7 +67,7 @@ mod general {
             deps.as_mut(),
             mock_env(),
             info.clone(),
-            get_init_msg(0,0)
+            get_init_msg(0, 1)
         ).unwrap();
 
         let config = Config {
@@ -54,13 +76,19 @@ mod general {
             dates: types_mint::Dates::default(),
             cost: types_mint::Costs::default(),
             burn: types_mint::Burn::default(),
-            token_supply: Default::default(),
-            wallet: types_mint::Wallet::default(),
+            token_supply: Uint128::from(10000u32),
+            wallet: types_mint::Wallet {
+                name: String::from("admin"),
+                wallet: Addr::unchecked("wallet"),
+            },
             max_mint_batch: Some(Uint128::from(8u32)),
             store_conf: Default::default(),
             token_total: Uint128::from(10000u32),
             frozen: false,
             paused: false,
+            token_id_scheme: Default::default(),
+            schema: None,
+            limits: Default::default(),
         };
 
         let msg : MigrateMsg<Config> = MigrateMsg::WithConfigClearState {
@@ -83,7 +111,7 @@ mod general {
             deps.as_mut(),
             mock_env(),
             info.clone(),
-            get_init_msg(0,0)
+            get_init_msg(0, 1)
         ).unwrap();
 
         let config = Config {
@@ -92,13 +120,19 @@ mod general {
             dates: types_mint::Dates::default(),
             cost: types_mint::Costs::default(),
             burn: types_mint::Burn::default(),
-            token_supply: Default::default(),
-            wallet: types_mint::Wallet::default(),
+            token_supply: Uint128::from(10000u32),
+            wallet: types_mint::Wallet {
+                name: String::from("admin"),
+                wallet: Addr::unchecked("wallet"),
+            },
             max_mint_batch: Some(Uint128::from(8u32)),
             store_conf: Default::default(),
             token_total: Uint128::from(10000u32),
             frozen: false,
             paused: false,
+            token_id_scheme: Default::default(),
+            schema: None,
+            limits: Default::default(),
         };
 
         let msg : MigrateMsg<Config> = MigrateMsg::WithConfig {
@@ -111,4 +145,236 @@ mod general {
         assert_eq!(res.attributes[0].value, String::from("migration"));
         assert_eq!(res.attributes[1].value, String::from("2.0.0"))
     }
+
+    #[test]
+    fn migrate_with_invalid_conf() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            get_init_msg(0, 1)
+        ).unwrap();
+
+        let config = Config {
+            creator: String::from(ADMIN),
+            name: String::from("nft2"),
+            dates: types_mint::Dates::default(),
+            cost: types_mint::Costs::default(),
+            burn: types_mint::Burn::default(),
+            token_supply: Default::default(),
+            wallet: types_mint::Wallet::default(),
+            max_mint_batch: Some(Uint128::from(8u32)),
+            store_conf: Default::default(),
+            token_total: Uint128::zero(),
+            frozen: false,
+            paused: false,
+            token_id_scheme: Default::default(),
+            schema: None,
+            limits: Default::default(),
+        };
+
+        let msg : MigrateMsg<Config> = MigrateMsg::WithConfig {
+          version: String::from("2.0.0"),
+          config
+        };
+
+        let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidWallet { address: String::from("") });
+    }
+
+    #[test]
+    fn migrate_with_conf_keeps_frozen() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            get_init_msg(0, 1)
+        ).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Freeze()).unwrap();
+
+        let mut config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        config.frozen = false;
+
+        let msg : MigrateMsg<Config> = MigrateMsg::WithConfig {
+          version: String::from("2.0.0"),
+          config
+        };
+
+        let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+
+        assert_eq!(err, ContractError::ContractFrozen {});
+
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        let locks: Locks = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Locks {}).unwrap()).unwrap();
+
+        assert!(config.frozen);
+        assert_eq!(locks, Locks::all());
+    }
+
+    #[test]
+    fn clear_state_drops_compact_tokens() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StoreConf(StoreConfMsg {
+                compact: true,
+                conf: Some(StoreConf {
+                    ipfs: String::from("ipfs://cid"),
+                    attributes: vec![String::from("background")],
+                    values: vec![vec![String::from("gold")]],
+                    ..Default::default()
+                }),
+                attributes: vec![vec![0]],
+            })
+        ).unwrap();
+
+        clear_state_migration(&mut deps);
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Store(get_mint_msg(String::from("0")))).unwrap();
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.token_uri, None);
+        assert_eq!(res.extension, None);
+    }
+
+    #[test]
+    fn clear_state_drops_metadata_locks() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Store(get_mint_msg(String::from("0")))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("0"),
+                set: vec![Trait {
+                    display_type: None,
+                    trait_type: String::from("level"),
+                    value: String::from("2"),
+                }],
+                remove: vec![],
+            }
+        ).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::LockMetadata { tokens: vec![String::from("0")] }).unwrap();
+
+        clear_state_migration(&mut deps);
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Store(get_mint_msg(String::from("0")))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateMetadata(UpdateMetadataMsg {
+                token_id: String::from("0"),
+                token_uri: Some(String::from("ipfs://cid/0.json")),
+                extension: None,
+            })
+        ).unwrap();
+
+        let changes: u64 = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TraitChanges { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(changes, 0);
+    }
+
+    #[test]
+    fn clear_state_drops_trait_indexes() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Store(get_mint_msg(String::from("0")))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("0"),
+                set: vec![Trait {
+                    display_type: None,
+                    trait_type: String::from("level"),
+                    value: String::from("2"),
+                }],
+                remove: vec![],
+            }
+        ).unwrap();
+
+        clear_state_migration(&mut deps);
+
+        let res: TraitCountsResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TraitCounts { trait_type: String::from("level") }
+        ).unwrap()).unwrap();
+
+        assert!(res.counts.is_empty());
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TokensByTrait {
+                trait_type: String::from("level"),
+                value: String::from("2"),
+                start_after: None,
+                limit: None,
+            }
+        ).unwrap()).unwrap();
+
+        assert!(res.tokens.is_empty());
+    }
+
+    #[test]
+    fn clear_state_drops_token_lists() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Store(get_mint_msg(String::from("0")))).unwrap();
+
+        clear_state_migration(&mut deps);
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StoredTokens { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert!(res.tokens.is_empty());
+
+        let res: TokenCountsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenCounts {}).unwrap()).unwrap();
+
+        assert_eq!(res, TokenCountsResponse { stored: 0, minted: 0 });
+    }
 }
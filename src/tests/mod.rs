@@ -7,4 +7,11 @@ pub mod pledge;
 pub mod burn;
 pub mod transfer;
 pub mod migration;
-pub mod query;
\ No newline at end of file
+pub mod query;
+pub mod roles;
+pub mod pause;
+pub mod metadata;
+pub mod rarity;
+pub mod schema;
+pub mod svg;
+pub mod stats;
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::{
+        from_binary,
+        Coin,
+    };
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use cw_utils::Scheduled;
+
+    use crate::error::ContractError;
+    use crate::msg::PauseScheduleResponse;
+    use crate::types_mint::PauseWindow;
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        tests::test_helpers::tests_helpers::{
+            now,
+            get_init_msg,
+            get_store_batch_msg,
+        }
+    };
+
+    const ADMIN: &str = "admin";
+    const MINTER: &str = "minter";
+    const DENOM: &str = "ujuno";
+
+    #[test]
+    fn pause_until() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Pause { until: Some(Scheduled::AtHeight(env.block.height + 10)) }
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "pause");
+
+        let minter = mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]);
+
+        let err = execute(deps.as_mut(), env.clone(), minter.clone(), ExecuteMsg::Mint()).unwrap_err();
+
+        assert_eq!(err, ContractError::ContractPaused {});
+
+        // the pause is lifted once the expiry is reached
+        env.block.height += 10;
+
+        let res = execute(deps.as_mut(), env, minter, ExecuteMsg::Mint()).unwrap();
+
+        assert_eq!(res.attributes[0].value, "mint");
+    }
+
+    #[test]
+    fn scheduled_window() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+
+        let window = PauseWindow {
+            start: Scheduled::AtHeight(env.block.height + 1),
+            end: Scheduled::AtHeight(env.block.height + 100),
+        };
+
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::SchedulePause(window.clone())).unwrap();
+
+        env.block.height += 1;
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::TransferNft { recipient: ADMIN.to_string(), token_id: String::from("0") }
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::ContractPausedScheduled {});
+
+        let res: PauseScheduleResponse = from_binary(
+            &query(deps.as_ref(), env.clone(), QueryMsg::PauseSchedule {}).unwrap()
+        ).unwrap();
+
+        assert!(res.paused);
+        assert_eq!(res.windows, vec![window]);
+
+        // the window is over
+        env.block.height += 100;
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[]),
+            ExecuteMsg::TransferNft { recipient: ADMIN.to_string(), token_id: String::from("0") }
+        ).unwrap();
+    }
+
+    #[test]
+    fn invalid_window() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::SchedulePause(PauseWindow {
+                start: Scheduled::AtHeight(200),
+                end: Scheduled::AtHeight(100),
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidPauseWindow {});
+    }
+}
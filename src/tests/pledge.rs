@@ -44,7 +44,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -110,7 +110,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -181,7 +181,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -259,7 +259,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -324,7 +324,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -1,3 +1,376 @@
 #[cfg(test)]
 mod general {
-}
\ No newline at end of file
+    use cosmwasm_schema::QueryResponses;
+    use cosmwasm_std::{from_binary, Coin, Uint128};
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use crate::error::ContractError;
+    use crate::msg::{AllNftInfoBatchResponse, NftInfoBatchResponse, TokenStatus, MintStatusResponse, SimulateBurnResponse, SimulateMintResponse, TokenCountsResponse, MintBatchMsg, UpdateConfigMsg, TransferOperation};
+    use crate::types_mint::{Limits, MintBlockedReason, MintPhase};
+    use cw721::{OperatorsResponse, TokensResponse};
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        tests::test_helpers::tests_helpers::{
+            now,
+            get_init_msg,
+            get_store_batch_msg,
+        }
+    };
+
+    const ADMIN: &str = "admin";
+    const MINTER: &str = "minter";
+    const DENOM: &str = "ujuno";
+
+    #[test]
+    fn mint_status() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        let res: MintStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::MintStatus {}).unwrap()).unwrap();
+
+        assert!(!res.open);
+        assert_eq!(res.reason, Some(MintBlockedReason::NothingStored));
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let res: MintStatusResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::MintStatus {}).unwrap()).unwrap();
+
+        assert!(res.open);
+        assert_eq!(res.reason, None);
+        assert_eq!(res.phase, MintPhase::Public);
+        assert_eq!(res.minted, 0);
+        assert_eq!(res.remaining, Uint128::from(5u32));
+        assert_eq!(res.price.amount, Uint128::from(4000000u32));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+
+        // unstored tokens leave the cw721 count behind, the status follows the token counts
+        execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), ExecuteMsg::Unstore { tokens: vec![String::from("4")] }).unwrap();
+
+        let res: MintStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::MintStatus {}).unwrap()).unwrap();
+        let counts: TokenCountsResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::TokenCounts {}).unwrap()).unwrap();
+
+        assert_eq!(res.minted, counts.minted);
+        assert_eq!(res.minted, 1);
+        assert_eq!(res.remaining, Uint128::from(counts.stored));
+        assert_eq!(res.remaining, Uint128::from(3u32));
+
+        // mock env time is past the end date
+        let res: MintStatusResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::MintStatus {}).unwrap()).unwrap();
+
+        assert_eq!(res.reason, Some(MintBlockedReason::Ended));
+        assert_eq!(res.phase, MintPhase::Ended);
+    }
+
+    #[test]
+    fn simulate_mint() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        let simulate = |funds: u128| QueryMsg::SimulateMint {
+            sender: MINTER.to_string(),
+            amount: Uint128::from(2u32),
+            funds: vec![Coin::new(funds, DENOM.to_string())],
+        };
+
+        let res: SimulateMintResponse = from_binary(&query(deps.as_ref(), env.clone(), simulate(8000000)).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("0"), String::from("1")]);
+        assert_eq!(res.cost, Some(Coin::new(8000000u128, DENOM.to_string())));
+        assert_eq!(res.error, None);
+
+        let res: SimulateMintResponse = from_binary(&query(deps.as_ref(), env, simulate(4000000)).unwrap()).unwrap();
+
+        assert!(res.tokens.is_empty());
+        assert_eq!(res.error, Some(ContractError::NotEnoughFunds {}.to_string()));
+    }
+
+    #[test]
+    fn simulate_burn() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Pledge { tokens: vec![String::from("0")] }).unwrap();
+
+        let simulate = |tokens: Vec<&str>| QueryMsg::SimulateBurn {
+            sender: ADMIN.to_string(),
+            tokens: tokens.into_iter().map(String::from).collect(),
+        };
+
+        let res: SimulateBurnResponse = from_binary(&query(deps.as_ref(), mock_env(), simulate(vec!["0"])).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("0")]);
+        assert_eq!(res.error, None);
+
+        let failed: SimulateBurnResponse = from_binary(&query(deps.as_ref(), mock_env(), simulate(vec!["0", "1"])).unwrap()).unwrap();
+
+        assert!(failed.tokens.is_empty());
+        assert_eq!(failed.error, Some(ContractError::TokenNotPledged { token_id: String::from("1") }.to_string()));
+
+        // nothing was burnt
+        let res: SimulateBurnResponse = from_binary(&query(deps.as_ref(), mock_env(), simulate(vec!["0"])).unwrap()).unwrap();
+
+        assert_eq!(res.error, None);
+
+        // the burn fails with the simulated error
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::BurnBatch { tokens: vec![String::from("0"), String::from("1")] }
+        ).unwrap_err();
+
+        assert_eq!(failed.error, Some(err.to_string()));
+    }
+
+    #[test]
+    fn stored_and_minted_tokens() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(8000000u128, DENOM.to_string())]),
+            ExecuteMsg::MintBatch(MintBatchMsg { amount: Uint128::from(2u32) })
+        ).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unstore { tokens: vec![String::from("4")] }).unwrap();
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StoredTokens { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("2"), String::from("3")]);
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::MintedTokens { start_after: Some(String::from("0")), limit: Some(5) }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("1")]);
+
+        let res: TokenCountsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenCounts {}).unwrap()).unwrap();
+
+        assert_eq!(res, TokenCountsResponse { stored: 2, minted: 2 });
+    }
+
+    #[test]
+    fn configurable_limits() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let mut init_msg = get_init_msg(0, 900);
+        init_msg.limits = Limits { max_token_batch: 2, default_query_limit: 3, ..Default::default() };
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StoredTokens { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("0"), String::from("1"), String::from("2")]);
+
+        let tokens = vec![String::from("0"), String::from("1"), String::from("2")];
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::BurnBatch { tokens }).unwrap_err();
+
+        assert_eq!(err, ContractError::RequestTooLarge { size: 3, max: 2 });
+
+        let tokens = vec![String::from("0"), String::from("1"), String::from("2")];
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::LockMetadata { tokens }).unwrap_err();
+
+        assert_eq!(err, ContractError::RequestTooLarge { size: 3, max: 2 });
+
+        // empty batches are accepted and change nothing
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::LockMetadata { tokens: vec![] }).unwrap();
+
+        // batch info queries are paged in the requested order
+        let tokens: Vec<String> = vec!["4", "3", "2", "1", "0"].into_iter().map(String::from).collect();
+        let page = |start_after: Option<&str>, limit: Option<u32>| -> Vec<String> {
+            let res: NftInfoBatchResponse = from_binary(&query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::NftInfoBatch { tokens: tokens.clone(), start_after: start_after.map(String::from), limit }
+            ).unwrap()).unwrap();
+
+            res.tokens.into_iter().map(|item| item.token_id).collect()
+        };
+
+        // without a limit the whole request is returned, not a default page
+        assert_eq!(page(None, None), tokens);
+        assert_eq!(page(None, Some(3)), vec![String::from("4"), String::from("3"), String::from("2")]);
+        assert_eq!(page(Some("2"), Some(3)), vec![String::from("1"), String::from("0")]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfoBatch { tokens: tokens.clone(), start_after: Some(String::from("9")), limit: None }
+        );
+
+        assert!(res.is_err());
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                limits: Some(Limits { default_query_limit: 40, ..Default::default() }),
+                ..Default::default()
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidLimits {});
+    }
+
+    #[test]
+    fn nft_info_batch() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(3))).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Pledge { tokens: vec![String::from("0")] }).unwrap();
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::BurnBatch { tokens: vec![String::from("0")] }).unwrap();
+
+        let tokens = vec![String::from("1"), String::from("0"), String::from("9")];
+
+        let res: NftInfoBatchResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfoBatch { tokens: tokens.clone(), start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        let ids: Vec<&str> = res.tokens.iter().map(|item| item.token_id.as_str()).collect();
+        let status: Vec<TokenStatus> = res.tokens.iter().map(|item| item.status.clone()).collect();
+
+        assert_eq!(ids, vec!["1", "0", "9"]);
+        assert_eq!(status, vec![TokenStatus::Found, TokenStatus::Burnt, TokenStatus::NotFound]);
+        assert_eq!(res.tokens[0].info.as_ref().unwrap().owner, ADMIN);
+        assert!(res.tokens[1].info.is_none());
+
+        let res: AllNftInfoBatchResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllNftInfoBatch { tokens, include_expired: None, start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens[0].info.as_ref().unwrap().access.owner, ADMIN);
+        assert_eq!(res.tokens[2].status, TokenStatus::NotFound);
+        assert!(res.tokens[2].info.is_none());
+    }
+
+    #[test]
+    fn query_response_schemas() {
+        let schemas = QueryMsg::response_schemas().unwrap();
+
+        assert!(schemas.contains_key("nft_info_batch"));
+        assert!(schemas.contains_key("mint_status"));
+        assert!(!schemas.contains_key("burnt_list"));
+    }
+
+    #[test]
+    fn approved_for_all() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ApproveAll { operator: MINTER.to_string(), expires: None }).unwrap();
+
+        let res: OperatorsResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ApprovedForAll { owner: ADMIN.to_string(), include_expired: None, start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.operators.len(), 1);
+        assert_eq!(res.operators[0].spender, MINTER);
+    }
+
+    #[test]
+    fn transferred_stored_tokens_are_untracked() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(3))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::TransferNft { recipient: MINTER.to_string(), token_id: String::from("2") }
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::TransferBatch(TransferOperation {
+                recipient: MINTER.to_string(),
+                tokens: vec![String::from("1")],
+            })
+        ).unwrap();
+
+        let res: TokensResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StoredTokens { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens, vec![String::from("0")]);
+
+        let res: TokenCountsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenCounts {}).unwrap()).unwrap();
+
+        assert_eq!(res, TokenCountsResponse { stored: 1, minted: 0 });
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::{from_binary, Uint128};
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use crate::error::ContractError;
+    use crate::msg::{RarityRankResponse, RarityResponse, UpdateMetadataMsg};
+    use crate::state::{LockArea, Metadata, Trait};
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        tests::test_helpers::tests_helpers::{
+            get_init_msg,
+            get_store_batch_msg,
+        }
+    };
+
+    const ADMIN: &str = "admin";
+
+    fn get_update_msg(token_id: &str, background: &str) -> UpdateMetadataMsg {
+        UpdateMetadataMsg {
+            token_id: token_id.to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                attributes: Some(vec![
+                    Trait { display_type: None, trait_type: String::from("background"), value: background.to_string() },
+                ]),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn rarity() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let mut init_msg = get_init_msg(0, 900);
+        init_msg.burn.owner_can_burn = true;
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(3))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdateMetadataBatch {
+                tokens: vec![
+                    get_update_msg("0", "gold"),
+                    get_update_msg("1", "gold"),
+                    get_update_msg("2", "silver"),
+                ],
+            }
+        ).unwrap();
+
+        let compute = ExecuteMsg::ComputeRarity { limit: Some(2), recompute_on_burn: Some(true) };
+
+        // traits can still change
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), compute.clone()).unwrap_err();
+
+        assert_eq!(err, ContractError::RarityMetadataUnlocked {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Lock { areas: vec![LockArea::Metadata] }
+        ).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), compute.clone()).unwrap();
+
+        assert_eq!(res.attributes[1].value, "2");
+        assert_eq!(res.attributes[2].value, "0");
+        assert_eq!(res.attributes[3].value, "false");
+
+        // the last token is scored and the ranking starts with the remaining batch
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), compute.clone()).unwrap();
+
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "1");
+        assert_eq!(res.attributes[3].value, "false");
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), compute).unwrap();
+
+        assert_eq!(res.attributes[1].value, "0");
+        assert_eq!(res.attributes[2].value, "2");
+        assert_eq!(res.attributes[3].value, "true");
+
+        let res: RarityResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Rarity { token_id: String::from("2") }
+        ).unwrap()).unwrap();
+
+        // 3 tokens, one silver
+        assert_eq!(res.score, Uint128::from(3_000_000u128));
+        assert_eq!(res.rank, Some(1));
+        assert!(!res.stale);
+
+        // ties are ranked by token id, from the highest
+        let res: RarityResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Rarity { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.rank, Some(3));
+
+        let res: RarityRankResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RarityRank { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens[0].token_id, "2");
+        assert_eq!(res.tokens[1].score, Uint128::from(1_500_000u128));
+        assert_eq!(res.tokens.len(), 3);
+
+        let res: RarityRankResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RarityRank { start_after: Some(String::from("2")), limit: Some(1) }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens.len(), 1);
+        assert_eq!(res.tokens[0].score, Uint128::from(1_500_000u128));
+
+        // burning the rarest token marks the scores stale
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Pledge { tokens: vec![String::from("2")] }).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Burn { token_id: String::from("2") }).unwrap();
+
+        let res: RarityRankResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RarityRank { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens.len(), 2);
+        assert!(res.stale);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::ComputeRarity { limit: None, recompute_on_burn: Some(true) }
+        ).unwrap();
+
+        assert_eq!(res.attributes[3].value, "true");
+
+        let res: RarityResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Rarity { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        // the burnt token no longer counts, 2 tokens left, both gold
+        assert_eq!(res.score, Uint128::from(1_000_000u128));
+        assert!(!res.stale);
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::{
+        from_binary,
+        Response,
+    };
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use crate::error::ContractError;
+    use crate::msg::RolesResponse;
+    use crate::state::Role;
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        tests::test_helpers::tests_helpers::{
+            get_init_msg,
+            get_mint_msg,
+        }
+    };
+
+    const ADMIN: &str = "admin";
+    const OPERATOR: &str = "operator";
+
+    #[test]
+    fn grant_and_revoke_role() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let res: Response = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::GrantRole { address: OPERATOR.to_string(), role: Role::Pauser }
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "grant_role");
+
+        let res: RolesResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Roles { address: OPERATOR.to_string() }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.roles, vec![Role::Pauser]);
+
+        // operator can pause
+        let res: Response = execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), ExecuteMsg::Pause { until: None }).unwrap();
+
+        assert_eq!(res.attributes[0].value, "pause");
+
+        // but can not store metadata
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPERATOR, &[]),
+            ExecuteMsg::Store(get_mint_msg(String::from("0")))
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::MissingRole { role: Role::MetadataManager });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::RevokeRole { address: OPERATOR.to_string(), role: Role::Pauser }
+        ).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OPERATOR, &[]), ExecuteMsg::Unpause()).unwrap_err();
+
+        assert_eq!(err, ContractError::MissingRole { role: Role::Pauser });
+    }
+
+    #[test]
+    fn creator_is_admin() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info, get_init_msg(0, 900)).unwrap();
+
+        let res: RolesResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Roles { address: ADMIN.to_string() }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.roles, vec![Role::Admin]);
+    }
+
+    #[test]
+    fn only_admin_can_grant() {
+        let mut deps = mock_dependencies();
+
+        instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), get_init_msg(0, 900)).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPERATOR, &[]),
+            ExecuteMsg::GrantRole { address: OPERATOR.to_string(), role: Role::Admin }
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::MissingRole { role: Role::Admin });
+    }
+}
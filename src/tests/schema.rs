@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use crate::error::ContractError;
+    use crate::msg::MintMsg;
+    use crate::state::{Metadata, Trait};
+    use crate::types_mint::{matches_pattern, CollectionSchema, TraitSchema};
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+        },
+        msg::ExecuteMsg,
+        tests::test_helpers::tests_helpers::get_init_msg,
+    };
+
+    const ADMIN: &str = "admin";
+
+    fn get_schema() -> CollectionSchema {
+        CollectionSchema {
+            traits: vec![
+                TraitSchema {
+                    trait_type: String::from("background"),
+                    values: vec![String::from("gold"), String::from("silver")],
+                    patterns: vec![],
+                    required: true,
+                },
+                TraitSchema {
+                    trait_type: String::from("level"),
+                    values: vec![],
+                    patterns: vec![String::from("level-*")],
+                    required: false,
+                },
+            ],
+            required_fields: vec![String::from("name")],
+            url_schemes: vec![String::from("ipfs://"), String::from("ar://")],
+            max_length: Some(20),
+        }
+    }
+
+    fn get_store_msg(name: Option<&str>, image: &str, traits: Vec<(&str, &str)>) -> ExecuteMsg {
+        ExecuteMsg::Store(MintMsg {
+            token_id: String::from("0"),
+            owner: ADMIN.to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                name: name.map(String::from),
+                image: Some(image.to_string()),
+                attributes: Some(traits
+                    .into_iter()
+                    .map(|(trait_type, value)| Trait {
+                        display_type: None,
+                        trait_type: trait_type.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect()),
+                ..Default::default()
+            }),
+        })
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("level-*", "level-3"));
+        assert!(matches_pattern("level-?", "level-3"));
+        assert!(matches_pattern("*-gold-*", "dark-gold-2"));
+        assert!(!matches_pattern("level-?", "level-10"));
+        assert!(!matches_pattern("level-*", "rank-1"));
+    }
+
+    #[test]
+    fn store_with_schema() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let mut init_msg = get_init_msg(0, 900);
+        init_msg.schema = Some(get_schema());
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        let cases = vec![
+            (
+                get_store_msg(None, "ipfs://cid/0.png", vec![("background", "gold")]),
+                ContractError::MissingMetadataField { token_id: String::from("0"), field: String::from("name") },
+            ),
+            (
+                get_store_msg(Some("nft"), "http://cid/0.png", vec![("background", "gold")]),
+                ContractError::InvalidUrlScheme {
+                    token_id: String::from("0"),
+                    field: String::from("image"),
+                    url: String::from("http://cid/0.png"),
+                },
+            ),
+            (
+                get_store_msg(Some("nft"), "ipfs://cid/0.png", vec![("backgruond", "gold")]),
+                ContractError::UnknownTraitType { token_id: String::from("0"), trait_type: String::from("backgruond") },
+            ),
+            (
+                get_store_msg(Some("nft"), "ipfs://cid/0.png", vec![("background", "bronze")]),
+                ContractError::InvalidTraitValue {
+                    token_id: String::from("0"),
+                    trait_type: String::from("background"),
+                    value: String::from("bronze"),
+                },
+            ),
+            (
+                get_store_msg(Some("nft"), "ipfs://cid/0.png", vec![("level", "level-1")]),
+                ContractError::MissingTrait { token_id: String::from("0"), trait_type: String::from("background") },
+            ),
+            (
+                get_store_msg(Some("a very long name for a token"), "ipfs://cid/0.png", vec![("background", "gold")]),
+                ContractError::FieldTooLong { token_id: String::from("0"), field: String::from("name"), max: 20 },
+            ),
+        ];
+
+        for (msg, expected) in cases {
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+
+            assert_eq!(err, expected);
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            get_store_msg(Some("nft"), "ar://cid/0.png", vec![("background", "gold"), ("level", "level-1")])
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "store");
+
+        // updates follow the same rules
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("0"),
+                set: vec![Trait { display_type: None, trait_type: String::from("level"), value: String::from("10") }],
+                remove: vec![],
+            }
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidTraitValue {
+            token_id: String::from("0"),
+            trait_type: String::from("level"),
+            value: String::from("10"),
+        });
+    }
+}
@@ -0,0 +1,387 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::{from_binary, Addr, Coin, Timestamp, Uint128};
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use crate::error::ContractError;
+    use crate::msg::{
+        BalanceAtHeightResponse,
+        BalanceResponse,
+        Holder,
+        HoldersResponse,
+        MigrateMsg,
+        MintBatchMsg,
+        MintStatusResponse,
+        OwnerOfAtHeightResponse,
+        StatsResponse,
+        TransferOperation,
+        UpdateConfigMsg,
+    };
+    use crate::state::PhaseMints;
+    use crate::types_mint::{Dates, Limits, MintBlockedReason, MintPhase};
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            migrate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        state::Config,
+        tests::test_helpers::tests_helpers::{
+            now,
+            get_init_msg,
+            get_store_batch_msg,
+        }
+    };
+
+    const ADMIN: &str = "admin";
+    const MINTER: &str = "minter";
+    const BUYER: &str = "buyer";
+    const DENOM: &str = "ujuno";
+
+    #[test]
+    fn stats() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(8000000u128, DENOM.to_string())]),
+            ExecuteMsg::MintBatch(MintBatchMsg { amount: Uint128::from(2u32) })
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::TransferNft { recipient: BUYER.to_string(), token_id: String::from("0") }
+        ).unwrap();
+
+        execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), ExecuteMsg::Pledge { tokens: vec![String::from("1")] }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), ExecuteMsg::Burn { token_id: String::from("1") }).unwrap();
+
+        let res: StatsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+
+        // the admin's stored tokens are not holdings, the buyer holds one
+        assert_eq!(res.holders, 1);
+        assert_eq!(res.buckets[0].holders, 1);
+        assert_eq!(res.buckets[1].min, 2);
+        assert_eq!(res.buckets[1].max, Some(4));
+        assert_eq!(res.buckets[1].holders, 0);
+        assert_eq!(res.buckets[5].max, None);
+        assert_eq!(res.burnt, 1);
+        assert_eq!(res.pledged, 0);
+        assert_eq!(res.revenue, vec![Coin::new(8000000u128, DENOM.to_string())]);
+        assert_eq!(res.mints.len(), 1);
+        assert_eq!(res.mints[0].count, 2);
+    }
+
+    #[test]
+    fn mints_per_phase() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+
+        // new dates open the next sale phase
+        let next_dates = Dates {
+            start: Some(Timestamp::from_seconds(0)),
+            end: Some(Timestamp::from_seconds(1800)),
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                dates: Some(next_dates.clone()),
+                ..Default::default()
+            })
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(BUYER, &[Coin::new(8000000u128, DENOM.to_string())]),
+            ExecuteMsg::MintBatch(MintBatchMsg { amount: Uint128::from(2u32) })
+        ).unwrap();
+
+        let res: StatsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+
+        assert_eq!(res.mints, vec![
+            PhaseMints {
+                phase: MintPhase::Public,
+                dates: Dates { start: Some(Timestamp::from_seconds(0)), end: Some(Timestamp::from_seconds(900)) },
+                count: 1,
+            },
+            PhaseMints { phase: MintPhase::Public, dates: next_dates, count: 2 },
+        ]);
+    }
+
+    #[test]
+    fn holders_and_balances() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(8000000u128, DENOM.to_string())]),
+            ExecuteMsg::MintBatch(MintBatchMsg { amount: Uint128::from(2u32) })
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::TransferBatch(TransferOperation {
+                recipient: BUYER.to_string(),
+                tokens: vec![String::from("0")],
+            })
+        ).unwrap();
+
+        let res: BalanceResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balance { owner: BUYER.to_string() }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.balance, 1);
+
+        // stored tokens held by the minter are not holdings
+        let res: BalanceResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balance { owner: ADMIN.to_string() }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.balance, 0);
+
+        let res: HoldersResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Holders { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.holders, vec![
+            Holder { address: Addr::unchecked(BUYER), balance: 1 },
+            Holder { address: Addr::unchecked(MINTER), balance: 1 },
+        ]);
+
+        let res: HoldersResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Holders { start_after: Some(BUYER.to_string()), limit: Some(1) }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.holders, vec![Holder { address: Addr::unchecked(MINTER), balance: 1 }]);
+    }
+
+    #[test]
+    fn ownership_at_height() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let at_height = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env.block.time = now();
+            env
+        };
+
+        instantiate(deps.as_mut(), at_height(100), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), at_height(100), info, ExecuteMsg::StoreBatch(get_store_batch_msg(2))).unwrap();
+
+        execute(
+            deps.as_mut(),
+            at_height(101),
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            at_height(102),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::TransferNft { recipient: BUYER.to_string(), token_id: String::from("0") }
+        ).unwrap();
+
+        let owner_at = |height: u64| -> Option<Addr> {
+            let res: OwnerOfAtHeightResponse = from_binary(&query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOfAtHeight { token_id: String::from("0"), height }
+            ).unwrap()).unwrap();
+
+            res.owner
+        };
+
+        // heights return the owner at the start of the block
+        assert_eq!(owner_at(100), None);
+        // stored tokens held by the minter have no owner history
+        assert_eq!(owner_at(101), None);
+        assert_eq!(owner_at(102), Some(Addr::unchecked(MINTER)));
+        assert_eq!(owner_at(103), Some(Addr::unchecked(BUYER)));
+
+        let balance_at = |owner: &str, height: u64| -> u64 {
+            let res: BalanceAtHeightResponse = from_binary(&query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::BalanceAtHeight { owner: owner.to_string(), height }
+            ).unwrap()).unwrap();
+
+            res.balance
+        };
+
+        assert_eq!(balance_at(ADMIN, 101), 0);
+        assert_eq!(balance_at(ADMIN, 102), 0);
+        assert_eq!(balance_at(MINTER, 102), 1);
+        assert_eq!(balance_at(MINTER, 103), 0);
+        assert_eq!(balance_at(BUYER, 103), 1);
+    }
+
+    #[test]
+    fn clear_snapshots_in_batches() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let mut init_msg = get_init_msg(0, 900);
+        init_msg.limits = Limits { max_token_batch: 2, ..Default::default() };
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(2))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[Coin::new(8000000u128, DENOM.to_string())]),
+            ExecuteMsg::MintBatch(MintBatchMsg { amount: Uint128::from(2u32) })
+        ).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::TransferNft { recipient: BUYER.to_string(), token_id: String::from("0") }
+        ).unwrap();
+
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::WithConfigClearState {
+            version: String::from("2.0.0"),
+            config
+        }).unwrap();
+
+        // the migration removes the two holder balances, two token owners are left
+        let res: HoldersResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Holders { start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert!(res.holders.is_empty());
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(1))).unwrap();
+
+        let res: MintStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::MintStatus {}).unwrap()).unwrap();
+
+        assert!(!res.open);
+        assert_eq!(res.reason, Some(MintBlockedReason::SnapshotsClearing));
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::ClearSnapshots { limit: Some(1) }).unwrap();
+
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "false");
+
+        // holdings can not move until the clear is done
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::SnapshotsNotCleared {});
+
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ClearSnapshots { limit: None }).unwrap();
+
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "true");
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+    }
+
+    #[test]
+    fn clear_state_without_snapshots() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::WithConfigClearState {
+            version: String::from("2.0.0"),
+            config
+        }).unwrap();
+
+        // nothing was recorded, so nothing is left to clear
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StoreBatch(get_store_batch_msg(1))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+    }
+}
@@ -1,9 +1,17 @@
 #[cfg(test)]
 mod general {
+    use cosmwasm_std::{from_binary, Coin, Uint128};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cw_multi_test::App;
     use roboto::{Roboto, RobotoContractData};
     use crate::error::ContractError;
-    use crate::msg::{StoreConfMsg, StoreConf, InstantiateMsg};
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{StoreConfMsg, StoreConf, InstantiateMsg, QueryMsg, UpdateMetadataMsg, NftInfoBatchResponse};
+    use crate::msg::{BatchStoreMsg, MintBatchMsg};
+    use crate::state::{Config, Extension, Trait};
+    use crate::types_mint::TokenIdScheme;
+    use cw721::{AllNftInfoResponse, NftInfoResponse};
+    use crate::tests::test_helpers::tests_helpers::now;
     use crate::tests::test_helpers::tests_helpers::{nft_custom_contract, get_mint_msg};
     use crate::{
         msg::ExecuteMsg,
@@ -15,6 +23,8 @@ mod general {
 
     const ADMIN: &str = "admin";
     const NFT_CUSTOM: &str = &"nft_custom";
+    const MINTER: &str = "minter";
+    const DENOM: &str = "ujuno";
 
     #[test]
     fn store() {
@@ -29,18 +39,26 @@ mod general {
 
         let store_one = ExecuteMsg::Store(get_mint_msg("0".to_string()));
 
-        let store_batch = ExecuteMsg::StoreBatch(get_store_batch_msg(20));
+        let store_batch = ExecuteMsg::StoreBatch(BatchStoreMsg {
+            batch: (1..=20).map(|id| get_mint_msg(id.to_string())).collect(),
+        });
 
         let store_conf = ExecuteMsg::StoreConf(StoreConfMsg {
+            compact: false,
             conf: Some(StoreConf {
                 name: String::from("nft"),
                 desc: String::from("nft"),
                 ipfs: String::from("nft"),
                 attributes: vec![String::from("value"), String::from("something")],
+                values: vec![
+                    vec![String::from("low"), String::from("high")],
+                    vec![String::from("something")],
+                ],
+                ..Default::default()
             }),
             attributes: vec![
-                vec![String::from("value"), String::from("something")],
-                vec![String::from("value"), String::from("something")]
+                vec![0, 0],
+                vec![1, 0]
             ],
         });
 
@@ -56,4 +74,346 @@ mod general {
                 assert_eq!(res.unwrap().events[1].attributes[1].value, "store_conf");
             }));
     }
+
+    #[test]
+    fn unstore_and_replace() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::StoreBatch(get_store_batch_msg(5))).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(4000000u128, DENOM.to_string())]),
+            ExecuteMsg::Mint()
+        ).unwrap();
+
+        // minted tokens can not be removed
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Unstore { tokens: vec![String::from("0")] }
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::TokenMinted { token_id: String::from("0") });
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Unstore { tokens: vec![String::from("4")] }
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "unstore");
+
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+        assert_eq!(config.token_total, Uint128::from(4u32));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::ReplaceStored(UpdateMetadataMsg {
+                token_id: String::from("3"),
+                token_uri: Some(String::from("ipfs://cid/3.json")),
+                extension: None,
+            })
+        ).unwrap();
+
+        assert_eq!(res.attributes[0].value, "replace_stored");
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::ReplaceStored(UpdateMetadataMsg {
+                token_id: String::from("0"),
+                token_uri: None,
+                extension: None,
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::TokenMinted { token_id: String::from("0") });
+    }
+
+    #[test]
+    fn token_id_scheme() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        let mut init_msg = get_init_msg(0, 900);
+        init_msg.token_id_scheme = TokenIdScheme::Padded { width: 4 };
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Store(get_mint_msg("0".to_string()))).unwrap_err();
+
+        assert_eq!(err, ContractError::TokenIdMismatch { token_id: String::from("0"), expected: String::from("0000") });
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Store(get_mint_msg(String::new()))).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::StoreConf(StoreConfMsg {
+                compact: false,
+                conf: Some(StoreConf {
+                    name: String::from("nft"),
+                    desc: String::from("nft"),
+                    ipfs: String::from("ipfs://cid"),
+                    attributes: vec![String::from("background")],
+                    values: vec![vec![String::from("gold"), String::from("silver")]],
+                    ..Default::default()
+                }),
+                attributes: vec![
+                    vec![0],
+                    vec![1],
+                ],
+            })
+        ).unwrap();
+
+        assert_eq!(res.attributes[1].value, "3");
+
+        let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+        assert_eq!(config.token_total, Uint128::from(3u32));
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0002") }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.extension.unwrap().name, Some(String::from("nft #2")));
+
+        let mut env = mock_env();
+        env.block.time = now();
+
+        // store conf tokens can be minted
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(MINTER, &[Coin::new(12000000u128, DENOM.to_string())]),
+            ExecuteMsg::MintBatch(MintBatchMsg { amount: Uint128::from(3u32) })
+        ).unwrap();
+
+        assert_eq!(res.attributes[5].value, "[\"0000\", \"0001\", \"0002\"]");
+    }
+
+    #[test]
+    fn store_conf_templates() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::StoreConf(StoreConfMsg {
+                compact: false,
+                conf: Some(StoreConf {
+                    name: String::from("nft"),
+                    desc: String::from("nft"),
+                    ipfs: String::from("ipfs://cid"),
+                    attributes: vec![String::from("background")],
+                    values: vec![vec![String::from("gold")]],
+                    image: Some(String::from("{ipfs}/images/{id}.{ext}")),
+                    image_extension: Some(String::from("gif")),
+                    animation_url: Some(String::from("{ipfs}/{trait:background}/{index}.{ext}")),
+                    animation_extension: Some(String::from("glb")),
+                    external_url: Some(String::from("https://nft.io/{name}/{id}")),
+                    token_uri: Some(String::from("{ipfs}/{id}.json")),
+                }),
+                attributes: vec![
+                    vec![0],
+                ],
+            })
+        ).unwrap();
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        let metadata = res.extension.unwrap();
+
+        assert_eq!(res.token_uri, Some(String::from("ipfs://cid/0.json")));
+        assert_eq!(metadata.image, Some(String::from("ipfs://cid/images/0.gif")));
+        assert_eq!(metadata.animation_url, Some(String::from("ipfs://cid/gold/0.glb")));
+        assert_eq!(metadata.external_url, Some(String::from("https://nft.io/nft/0")));
+    }
+
+    #[test]
+    fn store_conf_dictionary() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let conf = StoreConf {
+            attributes: vec![String::from("background"), String::from("eyes")],
+            values: vec![
+                vec![String::from("gold"), String::from("silver")],
+                vec![String::from("blue")],
+            ],
+            ..Default::default()
+        };
+
+        // short rows are rejected
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StoreConf(StoreConfMsg {
+                compact: false,
+                conf: Some(conf.clone()),
+                attributes: vec![vec![0, 0], vec![1]],
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::InvalidAttributeArity { row: 1, expected: 2, got: 1 });
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StoreConf(StoreConfMsg {
+                compact: false,
+                conf: Some(conf.clone()),
+                attributes: vec![vec![0, 1]],
+            })
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::AttributeIndexOutOfBounds {
+            row: 0,
+            trait_type: String::from("eyes"),
+            index: 1,
+        });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::StoreConf(StoreConfMsg {
+                compact: false,
+                conf: Some(conf),
+                attributes: vec![vec![1, 0]],
+            })
+        ).unwrap();
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        let attributes = res.extension.unwrap().attributes.unwrap();
+
+        assert_eq!(attributes[0].value, "silver");
+        assert_eq!(attributes[1].trait_type, "eyes");
+        assert_eq!(attributes[1].value, "blue");
+    }
+
+    #[test]
+    fn store_conf_compact() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        let conf = StoreConf {
+            ipfs: String::from("ipfs://cid"),
+            attributes: vec![String::from("background")],
+            values: vec![vec![String::from("gold"), String::from("silver")]],
+            ..Default::default()
+        };
+
+        // the same tokens stored with full metadata and with trait indexes only
+        for compact in [false, true] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::StoreConf(StoreConfMsg {
+                    compact,
+                    conf: Some(conf.clone()),
+                    attributes: vec![vec![1]],
+                })
+            ).unwrap();
+        }
+
+        let full: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        let compact: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("1") }
+        ).unwrap()).unwrap();
+
+        let metadata = compact.extension.unwrap();
+
+        assert_eq!(metadata.name, Some(String::from("nft #1")));
+        assert_eq!(metadata.image, Some(String::from("ipfs://cid/1.png")));
+        assert_eq!(metadata.attributes, full.extension.unwrap().attributes);
+
+        let res: AllNftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllNftInfo { token_id: String::from("1"), include_expired: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.access.owner, ADMIN);
+        assert_eq!(res.info.extension.unwrap().name, Some(String::from("nft #1")));
+
+        let res: NftInfoBatchResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfoBatch { tokens: vec![String::from("1")], start_after: None, limit: None }
+        ).unwrap()).unwrap();
+
+        assert_eq!(res.tokens[0].info.as_ref().unwrap().extension.as_ref().unwrap().image, Some(String::from("ipfs://cid/1.png")));
+
+        // updating a compact token keeps the rebuilt metadata
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UpdateTraits {
+                token_id: String::from("1"),
+                set: vec![Trait {
+                    display_type: None,
+                    trait_type: String::from("level"),
+                    value: String::from("2"),
+                }],
+                remove: vec![],
+            }
+        ).unwrap();
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("1") }
+        ).unwrap()).unwrap();
+
+        let metadata = res.extension.unwrap();
+
+        assert_eq!(metadata.name, Some(String::from("nft #1")));
+        assert_eq!(metadata.attributes.unwrap().len(), 2);
+    }
 }
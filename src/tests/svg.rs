@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod general {
+    use cosmwasm_std::from_binary;
+
+    use cosmwasm_std::testing::{
+        mock_dependencies,
+        mock_env,
+        mock_info,
+    };
+
+    use cw721::NftInfoResponse;
+
+    use crate::error::ContractError;
+    use crate::msg::MintMsg;
+    use crate::state::{Extension, LockArea, Metadata, SvgConf, SvgLayer, Trait};
+    use crate::{
+        contract::{
+            execute,
+            instantiate,
+            query,
+        },
+        msg::{
+            ExecuteMsg,
+            QueryMsg,
+        },
+        tests::test_helpers::tests_helpers::get_init_msg,
+    };
+
+    const ADMIN: &str = "admin";
+
+    fn get_layer(trait_type: &str, value: &str, svg: &str) -> SvgLayer {
+        SvgLayer {
+            trait_type: trait_type.to_string(),
+            value: value.to_string(),
+            svg: svg.to_string(),
+        }
+    }
+
+    #[test]
+    fn svg_layers() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(ADMIN, &[]);
+
+        instantiate(deps.as_mut(), mock_env(), info.clone(), get_init_msg(0, 900)).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Store(MintMsg {
+                token_id: String::from("0"),
+                owner: ADMIN.to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    attributes: Some(vec![
+                        Trait { display_type: None, trait_type: String::from("eyes"), value: String::from("blue") },
+                        Trait { display_type: None, trait_type: String::from("background"), value: String::from("gold") },
+                    ]),
+                    ..Default::default()
+                }),
+            })
+        ).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UploadLayers {
+                layers: vec![
+                    get_layer("background", "gold", "<rect fill=\"gold\"/>"),
+                    get_layer("eyes", "blue", "<circle fill=\"blue\"/>"),
+                ],
+            }
+        ).unwrap();
+
+        assert_eq!(res.attributes[1].value, "2");
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::SetSvgConf(SvgConf {
+                layer_order: vec![String::from("background"), String::from("eyes")],
+                view_box: String::from("0 0 32 32"),
+            })
+        ).unwrap();
+
+        let res: NftInfoResponse<Extension> = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo { token_id: String::from("0") }
+        ).unwrap()).unwrap();
+
+        // layers follow the configured order, not the trait order
+        assert_eq!(
+            res.extension.unwrap().image_data,
+            Some(String::from("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 32 32\"><rect fill=\"gold\"/><circle fill=\"blue\"/></svg>"))
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Lock { areas: vec![LockArea::Layers] }
+        ).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::UploadLayers { layers: vec![get_layer("eyes", "red", "<circle fill=\"red\"/>")] }
+        ).unwrap_err();
+
+        assert_eq!(err, ContractError::Locked { area: LockArea::Layers });
+    }
+}
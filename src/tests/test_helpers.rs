@@ -52,6 +52,9 @@ pub mod tests_helpers {
             can_burn_owned: false,
           },
           store_conf: Default::default(),
+          token_id_scheme: Default::default(),
+          schema: None,
+          limits: Default::default(),
       }
   }
 
@@ -44,7 +44,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -111,7 +111,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -183,7 +183,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
@@ -248,7 +248,7 @@ mod general {
         let mut deps = mock_dependencies();
         let mut info = mock_info(ADMIN, &[]);
 
-        let mut init_msg = get_init_msg(0,0);
+        let mut init_msg = get_init_msg(0, 1);
 
         // ENABLE OWNERS BURN
         init_msg.burn.owner_can_burn = true;
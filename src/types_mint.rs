@@ -1,5 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Uint128, Timestamp, Addr};
+use cosmwasm_std::{Uint128, Timestamp, Addr, BlockInfo};
+use cw_utils::Scheduled;
 
 const DEFAULT_DENOM: &str = "ujunox";
 const DEFAULT_AMOUNT: u64 = 10000000u64;
@@ -47,6 +48,81 @@ pub struct Dates {
   pub end: Option<Timestamp>,
 }
 
+// Max sizes per operation
+#[cw_serde]
+pub struct Limits {
+  // tokens per burn, transfer and pledge batch
+  pub max_token_batch: u32,
+  // tokens per nft info batch query
+  pub max_query_batch: u32,
+  // page size of paginated queries
+  pub default_query_limit: u32,
+  pub max_query_limit: u32,
+  // svg layers per upload
+  pub max_layer_batch: u32,
+  // tokens scored per compute rarity call
+  pub default_rarity_batch: u32,
+  pub max_rarity_batch: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+          max_token_batch: 30,
+          max_query_batch: 30,
+          default_query_limit: 10,
+          max_query_limit: 30,
+          max_layer_batch: 50,
+          default_rarity_batch: 100,
+          max_rarity_batch: 500,
+        }
+    }
+}
+
+impl Limits {
+  // Page size for a requested limit
+  pub fn query_limit(&self, limit: Option<u32>) -> usize {
+    limit.unwrap_or(self.default_query_limit).min(self.max_query_limit) as usize
+  }
+
+  // Batch size for a compute rarity call
+  pub fn rarity_batch(&self, limit: Option<u32>) -> usize {
+    limit.unwrap_or(self.default_rarity_batch).min(self.max_rarity_batch) as usize
+  }
+}
+
+// Sale phase from the mint dates
+#[cw_serde]
+pub enum MintPhase {
+  Upcoming,
+  Public,
+  Ended,
+}
+
+impl Dates {
+  pub fn phase(&self, time: &Timestamp) -> MintPhase {
+    match (&self.start, &self.end) {
+      (Some(start), _) if time < start => MintPhase::Upcoming,
+      (_, Some(end)) if time > end => MintPhase::Ended,
+      _ => MintPhase::Public,
+    }
+  }
+}
+
+// Why a mint would fail right now, mirrors the can_mint errors
+#[cw_serde]
+pub enum MintBlockedReason {
+  ContractFrozen,
+  ContractPaused,
+  NothingStored,
+  NotStarted,
+  Ended,
+  MaxTokenSupply,
+  SoldOut,
+  SnapshotsClearing,
+  Other,
+}
+
 // Who can burn the tokens
 #[cw_serde]
 #[derive(Default)]
@@ -56,4 +132,117 @@ pub struct Burn {
 
   // creator can burn tokens owned by others
   pub can_burn_owned: bool,
-}
\ No newline at end of file
+}
+
+// How stored tokens are keyed, the index is the position in the collection
+#[cw_serde]
+#[derive(Default)]
+pub enum TokenIdScheme {
+  // "0", "1", "2"
+  #[default]
+  Index,
+  // "{prefix}0", "{prefix}1"
+  Prefixed { prefix: String },
+  // zero padded index, "0000", "0001"
+  Padded { width: u32 },
+}
+
+impl TokenIdScheme {
+  pub fn token_id(&self, index: Uint128) -> String {
+    match self {
+      TokenIdScheme::Index => index.to_string(),
+      TokenIdScheme::Prefixed { prefix } => format!("{}{}", prefix, index),
+      TokenIdScheme::Padded { width } => format!("{:0width$}", index.u128(), width = *width as usize),
+    }
+  }
+}
+
+// Scheduled pause, start and end can be block heights or timestamps
+// mint, burn and transfer are not allowed while a window is active
+#[cw_serde]
+pub struct PauseWindow {
+  pub start: Scheduled,
+  pub end: Scheduled,
+}
+
+impl PauseWindow {
+  pub fn is_active(&self, block: &BlockInfo) -> bool {
+    self.start.is_triggered(block) && !self.end.is_triggered(block)
+  }
+
+  pub fn is_over(&self, block: &BlockInfo) -> bool {
+    self.end.is_triggered(block)
+  }
+}
+
+// Rules for a trait type of the collection schema
+#[cw_serde]
+pub struct TraitSchema {
+  pub trait_type: String,
+  // exact values, empty with no patterns accepts any value
+  pub values: Vec<String>,
+  // `*` matches any run of characters and `?` a single one, e.g. "level-*"
+  pub patterns: Vec<String>,
+  // every token needs this trait
+  pub required: bool,
+}
+
+impl TraitSchema {
+  pub fn accepts(&self, value: &str) -> bool {
+    if self.values.is_empty() && self.patterns.is_empty() {
+      return true
+    }
+
+    self.values.iter().any(|item| item == value)
+      || self.patterns.iter().any(|pattern| matches_pattern(pattern, value))
+  }
+}
+
+// Optional rules enforced on stored and updated metadata
+#[cw_serde]
+#[derive(Default)]
+pub struct CollectionSchema {
+  // allowed trait types, empty accepts any trait
+  pub traits: Vec<TraitSchema>,
+  // metadata fields that must be set, e.g. "name", "image"
+  pub required_fields: Vec<String>,
+  // allowed url prefixes, e.g. "ipfs://", "ar://", "https://", empty accepts any url
+  pub url_schemes: Vec<String>,
+  // max length of every metadata string and trait value
+  pub max_length: Option<u32>,
+}
+
+impl CollectionSchema {
+  pub fn accepts_url(&self, url: &str) -> bool {
+    self.url_schemes.is_empty() || self.url_schemes.iter().any(|scheme| url.starts_with(scheme.as_str()))
+  }
+}
+
+// Glob style match, `*` matches any run of characters and `?` a single one
+pub fn matches_pattern(pattern: &str, value: &str) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let value: Vec<char> = value.chars().collect();
+
+  let (mut p, mut v) = (0, 0);
+  // last star position in the pattern and the value position it matched from
+  let mut star: Option<(usize, usize)> = None;
+
+  while v < value.len() {
+    if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+      p += 1;
+      v += 1;
+    } else if p < pattern.len() && pattern[p] == '*' {
+      star = Some((p, v));
+      p += 1;
+    } else if let Some((star_p, star_v)) = star {
+      // let the star take one more character
+      p = star_p + 1;
+      v = star_v + 1;
+      star = Some((star_p, star_v + 1));
+    } else {
+      return false
+    }
+  }
+
+  pattern[p..].iter().all(|item| *item == '*')
+}